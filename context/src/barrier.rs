@@ -0,0 +1,37 @@
+flags! {
+    /// Set of memory barriers, used to order memory accesses of incoherent writes, like shader
+    /// storage or image stores from compute shaders, with the commands that read the written data
+    /// afterwards.
+    ///
+    /// Barriers can be combined with `|`.
+    pub struct BarrierFlags {
+        /// Vertex data sourced from buffers
+        const VERTEX_ATTRIB_ARRAY = 1;
+        /// Indices sourced from buffers
+        const ELEMENT_ARRAY = 1 << 1;
+        /// Uniforms sourced from buffers
+        const UNIFORM = 1 << 2;
+        /// Texture fetches from shaders
+        const TEXTURE_FETCH = 1 << 3;
+        /// Image loads, stores and atomics from shaders
+        const SHADER_IMAGE_ACCESS = 1 << 4;
+        /// Draw and dispatch parameters sourced from buffers
+        const COMMAND = 1 << 5;
+        /// Reads and writes of pixel buffers
+        const PIXEL_BUFFER = 1 << 6;
+        /// Texture uploads and downloads
+        const TEXTURE_UPDATE = 1 << 7;
+        /// Buffer updates, copies and mappings
+        const BUFFER_UPDATE = 1 << 8;
+        /// Reads and writes of render targets
+        const FRAMEBUFFER = 1 << 9;
+        /// Transform feedback writes
+        const TRANSFORM_FEEDBACK = 1 << 10;
+        /// Atomic counters sourced from buffers
+        const ATOMIC_COUNTER = 1 << 11;
+        /// Shader storage loads, stores and atomics
+        const SHADER_STORAGE = 1 << 12;
+        /// Every barrier above
+        const ALL = (1 << 13) - 1;
+    }
+}
@@ -12,8 +12,21 @@ pub unsafe trait FlatData {}
 #[derive(Copy, Clone)]
 pub enum Kind {
     Vertex,
+    /// Parameters of indirect draw or dispatch calls, see `Context::draw_arrays_indirect` and
+    /// `Context::dispatch_compute_indirect`
+    Indirect,
+    /// Uniform blocks of shaders
+    Uniform,
+    /// Shader storage blocks of shaders
+    Storage,
 }
 
+/// Uniform block binding of the constants set by `Context::set_draw_constants`
+pub const DRAW_CONSTANTS_BINDING: u32 = 0;
+
+/// Maximum size in bytes of the constants set by `Context::set_draw_constants`
+pub const MAX_DRAW_CONSTANTS_SIZE: usize = 256;
+
 #[derive(Default, Clone, Copy)]
 pub enum Access {
     #[default]
@@ -58,17 +71,125 @@ impl<'a, T: FlatData> Buffer<'a, T> {
             usage,
         }
     }
+
+    #[must_use]
+    pub const fn with_uniform_data(access: Access, usage: Usage, data: &'a [T]) -> Self {
+        Self {
+            data: Some(data),
+            kind: Kind::Uniform,
+            access,
+            usage,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_indirect_data(access: Access, usage: Usage, data: &'a [T]) -> Self {
+        Self {
+            data: Some(data),
+            kind: Kind::Indirect,
+            access,
+            usage,
+        }
+    }
 }
 
 pub trait Native {
-    /// Sets the data of the buffer
+    /// Sets the data of the buffer, which also replaces the element type and size.
     ///
     /// # Errors
     /// Depends on the native implementation.
     ///
     /// `Error::ConversionError`: When the length of the containing data can't be converted into
     /// the native type without wrapping or overflowing.
-    fn set_data<T: FlatData>(&mut self, data: &[T]) -> Result<(), Error>;
+    fn set_data<T: FlatData + 'static>(&mut self, data: &[T]) -> Result<(), Error>;
+
+    /// Size of the buffer in bytes
+    fn size(&self) -> usize;
+
+    /// Size in bytes of a single element of the data the buffer was created or last set with
+    fn element_size(&self) -> usize;
+
+    /// Type of the elements the buffer was created or last set with
+    fn element_type(&self) -> std::any::TypeId;
+
+    /// Name for diagnostics, set with `Context::set_buffer_name`
+    fn name(&self) -> Option<&str>;
+}
+
+flags! {
+    /// Access flags of a buffer mapping, see `Context::map_buffer_range`.
+    ///
+    /// Flags can be combined with `|`.
+    pub struct MapFlags {
+        /// The mapping can be read
+        const READ = 1;
+        /// The mapping can be written
+        const WRITE = 1 << 1;
+        /// The previous content of the range may be discarded, can't be combined with `READ`
+        const INVALIDATE_RANGE = 1 << 2;
+        /// The previous content of the whole buffer may be discarded, can't be combined with
+        /// `READ`
+        const INVALIDATE_BUFFER = 1 << 3;
+        /// Pending draws using the buffer are not waited for, writes to ranges they use are
+        /// undefined. Can't be combined with `READ`
+        const UNSYNCHRONIZED = 1 << 4;
+    }
+}
+
+/// Mapped range of a buffer, unmapped when dropped.
+///
+/// The bytes can only be accessed the way the flags of the mapping allow.
+pub struct Mapping<'a> {
+    data: &'a mut [u8],
+    flags: MapFlags,
+    unmap: Option<Box<dyn FnOnce() + 'a>>,
+}
+
+impl<'a> Mapping<'a> {
+    pub(crate) fn new(data: &'a mut [u8], flags: MapFlags, unmap: Box<dyn FnOnce() + 'a>) -> Self {
+        Self {
+            data,
+            flags,
+            unmap: Some(unmap),
+        }
+    }
+
+    #[must_use]
+    pub const fn flags(&self) -> MapFlags {
+        self.flags
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The mapped bytes, `None` without `MapFlags::READ`.
+    #[must_use]
+    pub fn as_slice(&self) -> Option<&[u8]> {
+        self.flags.contains(MapFlags::READ).then_some(&*self.data)
+    }
+
+    /// The mapped bytes, `None` without `MapFlags::WRITE`.
+    #[must_use]
+    pub fn as_mut_slice(&mut self) -> Option<&mut [u8]> {
+        self.flags
+            .contains(MapFlags::WRITE)
+            .then_some(&mut *self.data)
+    }
+}
+
+impl Drop for Mapping<'_> {
+    fn drop(&mut self) {
+        if let Some(unmap) = self.unmap.take() {
+            unmap();
+        }
+    }
 }
 
 unsafe impl FlatData for f32 {}
@@ -80,11 +201,15 @@ unsafe impl FlatData for u64 {}
 unsafe impl FlatData for i8 {}
 unsafe impl FlatData for i16 {}
 unsafe impl FlatData for i32 {}
+unsafe impl FlatData for cac_core::Color32 {}
 
 impl Display for Kind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Vertex => write!(f, "vertex"),
+            Self::Indirect => write!(f, "indirect"),
+            Self::Uniform => write!(f, "uniform"),
+            Self::Storage => write!(f, "storage"),
         }
     }
 }
@@ -0,0 +1,79 @@
+use crate::{mesh::Mesh, RenderTargetHandle, ShaderHandle};
+
+/// A single recorded call, see `CommandList`
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Clears the render target with its clear color, like `Context::clear`
+    Clear(RenderTargetHandle),
+    /// Clears only the depth of the render target, like `Context::clear_depth`
+    ClearDepth(RenderTargetHandle),
+    /// Draws the mesh with the shader onto the render target, like `Context::draw`
+    Draw {
+        target: RenderTargetHandle,
+        shader: ShaderHandle,
+        mesh: Mesh,
+    },
+}
+
+/// Calls recorded for a later `Context::submit`, for example to sort the draws by their state or
+/// to replay a frame.
+///
+/// Only the handles are stored, so the resources have to stay alive until the list is
+/// submitted.
+#[derive(Clone, Default)]
+pub struct CommandList {
+    commands: Vec<Command>,
+}
+
+impl CommandList {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+        }
+    }
+
+    pub fn clear(&mut self, target: RenderTargetHandle) -> &mut Self {
+        self.commands.push(Command::Clear(target));
+        self
+    }
+
+    pub fn clear_depth(&mut self, target: RenderTargetHandle) -> &mut Self {
+        self.commands.push(Command::ClearDepth(target));
+        self
+    }
+
+    pub fn draw(
+        &mut self,
+        target: RenderTargetHandle,
+        shader: ShaderHandle,
+        mesh: Mesh,
+    ) -> &mut Self {
+        self.commands.push(Command::Draw {
+            target,
+            shader,
+            mesh,
+        });
+        self
+    }
+
+    #[must_use]
+    pub const fn commands(&self) -> &[Command] {
+        self.commands.as_slice()
+    }
+
+    /// The recorded commands, to reorder or remove them before submitting
+    pub const fn commands_mut(&mut self) -> &mut Vec<Command> {
+        &mut self.commands
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
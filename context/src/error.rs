@@ -9,6 +9,11 @@ pub enum Error {
     FailedToLinkShader(String),
     ConversionFailed(&'static str),
     ExternalError(String),
+    ///The operation isn't allowed in the current state of the context
+    InvalidOperation(&'static str),
+    ///The context was lost, by a driver reset or a device removal,
+    ///it and all of its resources have to be recreated
+    ContextLost,
 }
 
 impl std::error::Error for Error {}
@@ -26,6 +31,8 @@ impl Display for Error {
             }
             Self::ConversionFailed(error) => write!(f, "conversion failed, caused by {error}"),
             Self::ExternalError(error) => write!(f, "external error, caused by {error}"),
+            Self::InvalidOperation(error) => write!(f, "invalid operation, caused by {error}"),
+            Self::ContextLost => write!(f, "context lost"),
         }
     }
 }
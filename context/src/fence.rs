@@ -0,0 +1,8 @@
+/// Outcome of waiting for a fence with `Context::wait_fence`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// The GPU finished all commands issued before the fence
+    Signaled,
+    /// The timeout ran out before the GPU reached the fence
+    TimedOut,
+}
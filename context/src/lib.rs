@@ -2,17 +2,33 @@
 #![warn(clippy::nursery)]
 #![warn(clippy::perf)]
 
+#[macro_use]
+mod flags;
+
 pub mod error;
 pub mod opengl;
 
+pub mod barrier;
 pub mod buffer;
+pub mod command;
+pub mod fence;
+pub mod mesh;
+pub mod pass;
+pub mod query;
 pub mod render_target;
 pub mod shader;
 pub mod vertex_layout;
 
-use cac_core::{gen_vec::Handle, math::URect};
+use cac_core::{
+    gen_vec::Handle,
+    math::{Mat4, Origin, URect},
+};
 
+pub use barrier::BarrierFlags;
 pub use buffer::Buffer;
+pub use command::{Command, CommandList};
+pub use mesh::Mesh;
+pub use pass::{DrawCommand, RenderPass};
 pub use render_target::RenderTarget;
 pub use vertex_layout::VertexLayout;
 
@@ -24,6 +40,9 @@ pub mod handle {
     pub struct Shader;
     pub struct Stage;
     pub struct RenderTarget;
+    pub struct Query;
+    pub struct PixelRead;
+    pub struct Fence;
 }
 
 pub type BufferHandle = Handle<handle::Buffer>;
@@ -31,6 +50,9 @@ pub type VertexLayoutHandle = Handle<handle::VertexLayout>;
 pub type StageHandle = Handle<handle::Stage>;
 pub type ShaderHandle = Handle<handle::Shader>;
 pub type RenderTargetHandle = Handle<handle::RenderTarget>;
+pub type QueryHandle = Handle<handle::Query>;
+pub type PixelReadHandle = Handle<handle::PixelRead>;
+pub type FenceHandle = Handle<handle::Fence>;
 
 pub trait Context {
     type Buffer: buffer::Native;
@@ -43,8 +65,135 @@ pub trait Context {
     fn update(&mut self);
 
     fn poll_errors(&mut self) -> Option<Vec<String>>;
+
+    /// Formats the bound resources and the render state into a readable, multi-line report.
+    ///
+    /// Meant for debugging unexpected draw results, the format is not stable.
+    fn debug_state_string(&self) -> String;
+
+    /// Whether the context supports the feature, to branch before calling functions that would
+    /// fail with `Error::InvalidContext` otherwise.
+    fn supports(&self, feature: Feature) -> bool;
+
+    /// Whether the driver reset the context, after which draws and resource creation fail with
+    /// `Error::ContextLost`.
+    ///
+    /// The status is checked once per `update`. A context without robust access, which is
+    /// requested on creation of the native context, never reports a reset and is never lost.
+    ///
+    /// `reset` doesn't recover a lost context, it has to be recreated with all of its resources.
+    fn is_lost(&self) -> bool;
+
+    /// Render target of the window, or the default framebuffer, at the viewport the context was
+    /// created with.
+    ///
+    /// It has no clear color and stays valid across `reset`, unlike the created render targets.
+    /// It can't be changed, `render_target_mut` returns `None` for it.
+    fn screen_target(&self) -> RenderTargetHandle;
     fn viewport(&self) -> URect;
 
+    /// Replaces the viewport, keeping the previous one on a stack to restore it with
+    /// `pop_viewport`.
+    ///
+    /// # Errors
+    /// `Error::ConversionError`: When the viewport can't be converted into the native values
+    /// without wrapping or overflowing.
+    fn push_viewport(&mut self, viewport: URect) -> Result<(), Error>;
+
+    /// Restores the viewport that was active before the last `push_viewport`.
+    ///
+    /// Returns the removed viewport, or `None` if there was no pushed viewport.
+    fn pop_viewport(&mut self) -> Option<URect>;
+
+    /// Sets the origin of the point sprite coordinates(`gl_PointCoord`) used when rasterizing
+    /// `Primitive::Points`.
+    ///
+    /// Defaults to `Origin::TopLeft`.
+    fn set_point_coord_origin(&mut self, origin: Origin);
+
+    /// Allows the vertex shader to set the size of rasterized points via `gl_PointSize`.
+    ///
+    /// Disabled by default.
+    fn set_program_point_size(&mut self, enabled: bool);
+
+    /// Uses the alpha of the fragment to decide how many samples of a pixel it covers, giving
+    /// smooth edges to cutout transparency, like foliage.
+    /// Only has an effect when rendering into a multisampled target.
+    ///
+    /// Disabled by default.
+    fn set_alpha_to_coverage(&mut self, enabled: bool);
+
+    /// Discards fragments that are farther away than the depth already stored in the render
+    /// target, so that nearer geometry stays in front regardless of the draw order.
+    ///
+    /// Disabled by default.
+    fn set_depth_test(&mut self, enabled: bool);
+
+    /// Limits draws and clears to the viewport of the bound render target.
+    ///
+    /// Without it, clearing a render target clears the whole framebuffer, like in plain OpenGL.
+    /// `clear_rect` stays limited to its rect either way.
+    ///
+    /// Enabled by default.
+    fn set_scissor_test(&mut self, enabled: bool);
+
+    /// Filters across the faces of cube maps, instead of sampling each face on its own, which
+    /// avoids visible seams between the faces.
+    ///
+    /// Disabled by default.
+    fn set_seamless_cubemaps(&mut self, enabled: bool);
+
+    /// Orders the memory accesses of the commands issued before the barrier with the accesses of
+    /// the commands issued after it.
+    ///
+    /// Writes from shaders, like shader storage or image stores, are not automatically visible
+    /// to subsequent commands. The barriers define which kind of accesses need to see them.
+    fn memory_barrier(&mut self, barriers: BarrierFlags);
+
+    /// Runs the fragment shader for at least the fraction of the samples of each pixel, instead
+    /// of once per pixel, which reduces aliasing inside of triangles with multisampling.
+    ///
+    /// `Some(rate)` enables sample shading with the minimum rate clamped to 0.0 - 1.0, `None`
+    /// disables it. Disabled by default.
+    fn set_sample_shading(&mut self, min_sample_rate: Option<f32>);
+
+    /// Sets the clip space conventions, the corner of the window origin and the depth range.
+    ///
+    /// A `ClipDepth::ZeroToOne` range makes better use of the depth precision, especially with a
+    /// reversed depth(near at 1.0, far at 0.0).
+    ///
+    /// Defaults to `ClipOrigin::LowerLeft` and `ClipDepth::NegativeOneToOne`.
+    ///
+    /// # Errors
+    /// `Error::InvalidContext`: When the context doesn't support clip control.
+    fn set_clip_control(&mut self, origin: ClipOrigin, depth: ClipDepth) -> Result<(), Error>;
+
+    /// Maps the normalized device depth onto the window depth range between near and far, for
+    /// example to composite multiple scenes into one depth buffer.
+    ///
+    /// The values are clamped to 0.0 - 1.0. Defaults to near 0.0 and far 1.0.
+    fn set_depth_range(&mut self, near: f32, far: f32);
+
+    /// Chooses the vertex of a primitive that provides the values of `flat` varyings, for
+    /// example the color of a flat shaded face.
+    ///
+    /// Defaults to `ProvokingVertex::Last`.
+    fn set_provoking_vertex(&mut self, mode: ProvokingVertex);
+
+    /// Offsets the depth of filled polygons by `factor` times their depth slope plus `units`
+    /// times the smallest resolvable depth difference, for example to draw decals on top of
+    /// coplanar geometry without z-fighting.
+    ///
+    /// The offset is disabled when both are 0.0, which is the default.
+    fn set_polygon_offset(&mut self, factor: f32, units: f32);
+
+    /// Combines the fragment color with the color in the render target by a bitwise operation,
+    /// for example `LogicOp::Xor` for rubber band selections that are erased by drawing them
+    /// again. Replaces blending while enabled.
+    ///
+    /// Disabled with `None`, which is the default.
+    fn set_logic_op(&mut self, op: Option<LogicOp>);
+
     /// Invokes a drawcall, binding the shader, layout and rendetarget
     ///
     /// # Errors
@@ -61,6 +210,209 @@ pub trait Context {
         count: usize,
     ) -> Result<(), Error>;
 
+    /// Starts an empty list of commands, which are replayed by `submit` instead of being executed
+    /// right away.
+    fn record(&self) -> CommandList {
+        CommandList::new()
+    }
+
+    /// Executes the recorded commands in order, through the same binding path as the direct
+    /// calls.
+    ///
+    /// # Errors
+    /// `Error::ResourceNotFound`: When a handle of a command is invalid. The commands after the
+    /// failed one are skipped.
+    /// See `draw` for the other errors.
+    fn submit(&mut self, list: &CommandList) -> Result<(), Error> {
+        for command in list.commands() {
+            match *command {
+                Command::Clear(target) => self.clear(target)?,
+                Command::ClearDepth(target) => self.clear_depth(target)?,
+                Command::Draw {
+                    target,
+                    shader,
+                    mesh,
+                } => self.draw(
+                    target,
+                    mesh.primitive,
+                    shader,
+                    mesh.layout,
+                    mesh.start,
+                    mesh.count,
+                )?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies the state of the pass once and then issues every draw with it, instead of
+    /// repeating the same state for each `draw`.
+    ///
+    /// # Errors
+    /// `Error::ResourceNotFound`: When the target or the handles of a draw are invalid.
+    /// The draws after the failed one are skipped.
+    /// See `draw` and `push_viewport` for the other errors.
+    fn run_pass(&mut self, pass: &RenderPass, draws: &[DrawCommand]) -> Result<(), Error>;
+
+    /// Uploads per draw data, like a model matrix, and binds it to the uniform block at
+    /// `buffer::DRAW_CONSTANTS_BINDING` for the following draws.
+    ///
+    /// The constants are written into a ring of small uniform buffer ranges, so that setting
+    /// them for many draws doesn't need an uniform call per value. The data has to follow the
+    /// std140 layout of the uniform block.
+    ///
+    /// # Errors
+    /// `Error::InvalidOperation`: When the data is larger than `buffer::MAX_DRAW_CONSTANTS_SIZE`.
+    fn set_draw_constants<T: buffer::FlatData>(&mut self, data: &T) -> Result<(), Error>;
+
+    /// Invokes a drawcall like `draw`, with the transform set to the `u_transform` mat4 uniform
+    /// of the shader(`shader::TRANSFORM_UNIFORM`). Shaders without the uniform draw without it.
+    ///
+    /// # Errors
+    /// See `draw`.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_transformed(
+        &mut self,
+        target: RenderTargetHandle,
+        primitive: Primitive,
+        shader: ShaderHandle,
+        layout: VertexLayoutHandle,
+        start: usize,
+        count: usize,
+        transform: Mat4,
+    ) -> Result<(), Error>;
+
+    /// Invokes a drawcall without any vertex attributes, binding the shader and rendertarget.
+    ///
+    /// The vertex shader has to generate the vertices itself, for example from `gl_VertexID`,
+    /// like for fullscreen triangles.
+    ///
+    /// # Errors
+    /// `Error::ResourceNotFound`: When the handles are invalid and are not pointing to actual resources
+    /// `Error::ConversionError`: When count can't be converted into the native graphics API
+    /// value, like i32, without wrapping or overflowing.
+    fn draw_arrays_no_layout(
+        &mut self,
+        target: RenderTargetHandle,
+        primitive: Primitive,
+        shader: ShaderHandle,
+        count: usize,
+    ) -> Result<(), Error>;
+
+    /// Invokes a drawcall with the parameters read from the buffer at the byte offset, binding
+    /// the shader, layout and rendertarget.
+    ///
+    /// The parameters are four u32: vertex count, instance count, first vertex and base
+    /// instance. They can be written by the GPU, for example by a compute shader.
+    ///
+    /// # Errors
+    /// `Error::ResourceNotFound`: When the handles are invalid and are not pointing to actual resources
+    /// `Error::InvalidOperation`: When the offset isn't a multiple of 4, or the parameters don't
+    /// fit into the buffer.
+    fn draw_arrays_indirect(
+        &mut self,
+        target: RenderTargetHandle,
+        primitive: Primitive,
+        shader: ShaderHandle,
+        layout: VertexLayoutHandle,
+        indirect_buffer: BufferHandle,
+        offset: usize,
+    ) -> Result<(), Error>;
+
+    /// Invokes `draw_count` drawcalls with one call, reading the parameters of each from the
+    /// buffer, starting at the beginning of it, like `draw_arrays_indirect`.
+    ///
+    /// The stride is the distance in bytes between the parameters of consecutive draws, 0 means
+    /// they are tightly packed. It allows the parameters to be part of larger structs, for
+    /// example written by a culling compute shader.
+    ///
+    /// # Errors
+    /// `Error::ResourceNotFound`: When the handles are invalid and are not pointing to actual resources
+    /// `Error::InvalidOperation`: When the stride isn't 0 or a multiple of 4, that is at least 16,
+    /// or the parameters of the draws don't fit into the buffer.
+    /// `Error::ConversionError`: When the draw count or the stride can't be converted into the
+    /// native graphics API value.
+    #[allow(clippy::too_many_arguments)]
+    fn multi_draw_indirect(
+        &mut self,
+        target: RenderTargetHandle,
+        primitive: Primitive,
+        shader: ShaderHandle,
+        layout: VertexLayoutHandle,
+        indirect_buffer: BufferHandle,
+        draw_count: usize,
+        stride: usize,
+    ) -> Result<(), Error>;
+
+    /// Dispatches the compute shader with the work group counts read from the buffer at the byte
+    /// offset.
+    ///
+    /// The work group counts are three u32, for x, y and z.
+    ///
+    /// # Errors
+    /// `Error::ResourceNotFound`: When the handles are invalid and are not pointing to actual resources
+    /// `Error::InvalidOperation`: When the offset isn't a multiple of 4 or the work group counts
+    /// at the offset end past the buffer.
+    /// `Error::ConversionError`: When the offset can't be converted into the native graphics API
+    /// value without wrapping or overflowing.
+    fn dispatch_compute_indirect(
+        &mut self,
+        shader: ShaderHandle,
+        indirect_buffer: BufferHandle,
+        offset: usize,
+    ) -> Result<(), Error>;
+
+    /// Starts a query that counts until `end_query`, for example the samples of draws for
+    /// occlusion tests.
+    ///
+    /// Only one query can be active at a time.
+    ///
+    /// # Errors
+    /// `Error::InvalidOperation`: When another query is still active.
+    fn begin_query(&mut self, kind: query::Kind) -> Result<(), Error>;
+
+    /// Ends the active query and returns the handle to retrieve its result.
+    ///
+    /// # Errors
+    /// `Error::InvalidOperation`: When there is no active query.
+    fn end_query(&mut self) -> Result<QueryHandle, Error>;
+
+    /// Returns the result of an ended query without waiting for the GPU.
+    ///
+    /// Returns `None` while the result isn't available yet, or if the handle doesn't belong to
+    /// an ended query.
+    fn query_result(&self, handle: QueryHandle) -> Option<u64>;
+
+    /// Deletes the query.
+    ///
+    /// # Errors
+    /// `Error::ResourceNotFound`: When the handle doesn't belong to a query.
+    /// `Error::InvalidOperation`: When the query is still active.
+    fn delete_query(&mut self, handle: QueryHandle) -> Result<(), Error>;
+
+    /// Inserts a fence after all commands issued so far, to find out when the GPU finished them
+    /// with `wait_fence`, without stalling on everything like `glFinish`.
+    ///
+    /// # Errors
+    /// `Error::ContextLost`: When the context was reset by the driver.
+    /// `Error::ExternalError`: When the native fence can't be created.
+    fn insert_fence(&mut self) -> Result<FenceHandle, Error>;
+
+    /// Blocks until the GPU finished all commands before the fence, or the timeout in
+    /// nanoseconds runs out. A timeout of 0 only checks the fence.
+    ///
+    /// # Errors
+    /// `Error::ResourceNotFound`: When the handle doesn't belong to a fence.
+    /// `Error::ExternalError`: When the driver fails to wait.
+    fn wait_fence(&self, fence: FenceHandle, timeout_ns: u64) -> Result<fence::Status, Error>;
+
+    /// Deletes the fence.
+    ///
+    /// # Errors
+    /// `Error::ResourceNotFound`: When the handle doesn't belong to a fence.
+    fn delete_fence(&mut self, fence: FenceHandle) -> Result<(), Error>;
+
     /// Creates a render target, a surface to draw onto
     ///
     /// # Errors
@@ -76,6 +428,56 @@ pub trait Context {
     fn render_target(&self, handle: RenderTargetHandle) -> Option<&Self::RenderTarget>;
     fn render_target_mut(&mut self, handle: RenderTargetHandle) -> Option<&mut Self::RenderTarget>;
 
+    /// Clears the render target with its clear color, like `render_target::Native::clear`, but
+    /// binds the render target first.
+    ///
+    /// # Errors
+    /// `Error::ResourceNotFound`: When the handle doesn't belong to a render target.
+    fn clear(&mut self, target: RenderTargetHandle) -> Result<(), Error>;
+
+    /// Clears only the depth of the render target, keeping the color, for example to draw a UI
+    /// over an already rendered 3D scene.
+    ///
+    /// # Errors
+    /// `Error::ResourceNotFound`: When the handle doesn't belong to a render target.
+    fn clear_depth(&mut self, target: RenderTargetHandle) -> Result<(), Error>;
+
+    /// Clears only the color of the render target to its clear color, keeping the depth.
+    /// Does nothing when the render target has no clear color.
+    ///
+    /// # Errors
+    /// `Error::ResourceNotFound`: When the handle doesn't belong to a render target.
+    fn clear_color_only(&mut self, target: RenderTargetHandle) -> Result<(), Error>;
+
+    /// Starts reading the pixels of the render target without waiting for the GPU, unlike
+    /// `render_target::Native::read_pixels`.
+    ///
+    /// Poll the result with `pixel_read_result`, for example in the next frame.
+    ///
+    /// # Errors
+    /// `Error::ResourceNotFound`: When the handle doesn't belong to a render target.
+    /// `Error::ConversionError`: When the rect can't be converted into the native values
+    /// without wrapping or overflowing.
+    fn read_pixels_async(
+        &mut self,
+        target: RenderTargetHandle,
+        format: cac_core::image::Format,
+        rect: URect,
+    ) -> Result<PixelReadHandle, Error>;
+
+    /// Returns the pixels of a read started with `read_pixels_async`, or `None` if the GPU
+    /// hasn't finished reading them yet.
+    ///
+    /// Once the pixels are returned, the handle becomes invalid.
+    ///
+    /// # Errors
+    /// `Error::ResourceNotFound`: When the handle doesn't belong to a pending read.
+    /// `Error::ExternalError`: When the native read fails.
+    fn pixel_read_result(
+        &mut self,
+        handle: PixelReadHandle,
+    ) -> Result<Option<cac_core::image::Image>, Error>;
+
     /// Creates a buffer, data that is stored on the graphics context.
     /// It doesn't neccessarily mean that the data is stored on the GPU, but is dependent on the
     /// actual graphics implementation.
@@ -85,13 +487,96 @@ pub trait Context {
     ///
     /// `Error::ConversionError`: When the length of the containing data can't be converted into
     /// the native type without wrapping or overflowing.
-    fn create_buffer<T: buffer::FlatData>(
+    fn create_buffer<T: buffer::FlatData + 'static>(
         &mut self,
         buffer: &Buffer<T>,
     ) -> Result<BufferHandle, Error>;
+
+    /// Creates a vertex buffer from the items, writing them straight into the storage of the
+    /// buffer instead of collecting them into a slice first.
+    ///
+    /// The size is taken from the length of the iterator. If it yields fewer items, the rest of
+    /// the buffer is left uninitialized.
+    ///
+    /// # Errors
+    /// `Error::ConversionError`: When the size of the data can't be converted into the native
+    /// type without wrapping or overflowing.
+    /// `Error::InvalidOperation`: When the storage of the buffer can't be mapped.
+    fn create_buffer_from_iter<T: buffer::FlatData + 'static>(
+        &mut self,
+        items: impl ExactSizeIterator<Item = T>,
+        access: buffer::Access,
+        usage: buffer::Usage,
+    ) -> Result<BufferHandle, Error>;
     fn buffer(&self, handle: BufferHandle) -> Option<&Self::Buffer>;
     fn buffer_mut(&mut self, handle: BufferHandle) -> Option<&mut Self::Buffer>;
 
+    /// Size in bytes of a single element of the data the buffer was created or last set with.
+    ///
+    /// Returns `None` if the handle doesn't belong to a buffer.
+    fn buffer_element_size(&self, handle: BufferHandle) -> Option<usize>;
+
+    /// Orphans the storage of the buffer, by re-specifying it with the same size and usage but
+    /// without data.
+    ///
+    /// The GPU can keep using the old storage for pending draws, so updating the buffer right
+    /// after doesn't have to wait for them. The content of the buffer is undefined until it
+    /// gets new data.
+    ///
+    /// # Errors
+    /// `Error::ResourceNotFound`: When the handle doesn't belong to a buffer.
+    fn orphan_buffer(&mut self, handle: BufferHandle) -> Result<(), Error>;
+
+    /// Binds `size` bytes of the buffer, starting at the byte offset, to the binding point of
+    /// the uniform or shader storage blocks, so that one buffer can hold the data of many blocks.
+    ///
+    /// # Errors
+    /// `Error::ResourceNotFound`: When the handle doesn't belong to a buffer.
+    /// `Error::InvalidOperation`: When the kind is neither `Uniform` nor `Storage`, the offset
+    /// isn't a multiple of the offset alignment of the kind, the size is 0 or the range is
+    /// outside of the buffer.
+    /// `Error::ConversionError`: When the values can't be converted into the native types.
+    fn bind_buffer_range(
+        &mut self,
+        handle: BufferHandle,
+        binding: u32,
+        offset: usize,
+        size: usize,
+        kind: buffer::Kind,
+    ) -> Result<(), Error>;
+
+    /// Maps `length` bytes of the buffer, starting at the byte offset, into memory.
+    ///
+    /// The buffer is unmapped when the mapping is dropped, it must not be used by draws until
+    /// then. The mapping only allows the accesses of the flags, see `buffer::MapFlags`.
+    ///
+    /// # Errors
+    /// `Error::ResourceNotFound`: When the handle doesn't belong to a buffer.
+    /// `Error::InvalidOperation`: When the range is outside of the buffer, the flags contain
+    /// neither read nor write, read is combined with invalidation or unsynchronized access, or
+    /// the buffer fails to map.
+    fn map_buffer_range(
+        &mut self,
+        handle: BufferHandle,
+        offset: usize,
+        length: usize,
+        flags: buffer::MapFlags,
+    ) -> Result<buffer::Mapping<'_>, Error>;
+
+    /// Deletes the buffer.
+    /// Layouts referencing the buffer are not updated, drawing with them is reported as an error
+    /// in debug builds.
+    ///
+    /// # Errors
+    /// `Error::ResourceNotFound`: When the handle doesn't belong to a buffer.
+    fn delete_buffer(&mut self, handle: BufferHandle) -> Result<(), Error>;
+
+    /// Attaches a name to the buffer for diagnostics, like the trace logs when it's dropped.
+    ///
+    /// # Errors
+    /// `Error::ResourceNotFound`: When the handle doesn't belong to a buffer.
+    fn set_buffer_name(&mut self, handle: BufferHandle, name: &str) -> Result<(), Error>;
+
     /// Creates the vertex layout
     ///
     ///
@@ -104,9 +589,36 @@ pub trait Context {
     /// `Error::ResourceNotFound`: When a buffer handle is invalid and not pointing to a graphics
     /// object.
     fn create_layout(&mut self, layout: &VertexLayout) -> Result<VertexLayoutHandle, Error>;
+
+    /// Creates a vertex layout with the attributes sourced from a single buffer, starting at
+    /// the beginning of the buffer.
+    ///
+    /// # Errors
+    /// See `create_layout`.
+    fn create_simple_layout(
+        &mut self,
+        buffer: BufferHandle,
+        attributes: &[vertex_layout::VertexAttribute],
+        stride: vertex_layout::Stride,
+    ) -> Result<VertexLayoutHandle, Error> {
+        self.create_layout(&VertexLayout {
+            attributes: vec![vertex_layout::BufferAttributes {
+                attributes: attributes.to_vec(),
+                buffer: Some(buffer),
+                stride,
+                offset: 0,
+            }],
+        })
+    }
     fn layout(&self, handle: VertexLayoutHandle) -> Option<&Self::Layout>;
     fn layout_mut(&mut self, handle: VertexLayoutHandle) -> Option<&mut Self::Layout>;
 
+    /// Attaches a name to the layout for diagnostics, like the trace logs when it's dropped.
+    ///
+    /// # Errors
+    /// `Error::ResourceNotFound`: When the handle doesn't belong to a layout.
+    fn set_layout_name(&mut self, handle: VertexLayoutHandle, name: &str) -> Result<(), Error>;
+
     /// Creates a new shader stage
     ///
     /// # Errors
@@ -118,6 +630,12 @@ pub trait Context {
     fn create_stage(&mut self, shader: shader::Stage) -> Result<StageHandle, Error>;
     fn stage(&self, handle: StageHandle) -> Option<&Self::Stage>;
 
+    /// Attaches a name to the stage for diagnostics, like the trace logs when it's dropped.
+    ///
+    /// # Errors
+    /// `Error::ResourceNotFound`: When the handle doesn't belong to a stage.
+    fn set_stage_name(&mut self, handle: StageHandle, name: &str) -> Result<(), Error>;
+
     /// Creates a new shader program
     ///
     /// # Errors
@@ -129,6 +647,66 @@ pub trait Context {
     /// object.
     fn create_shader(&mut self, shader: shader::Shader) -> Result<ShaderHandle, Error>;
     fn shader(&self, handle: ShaderHandle) -> Option<&Self::Shader>;
+
+    /// Attaches a name to the shader for diagnostics, like the trace logs when it's dropped.
+    ///
+    /// # Errors
+    /// `Error::ResourceNotFound`: When the handle doesn't belong to a shader.
+    fn set_shader_name(&mut self, handle: ShaderHandle, name: &str) -> Result<(), Error>;
+
+    /// Lists the active attributes and uniforms of a shader program
+    ///
+    /// # Errors
+    /// `ResourceNotFound`: When the handle doesn't point to a shader program.
+    /// `ConversionError`: When the native values can't be converted.
+    fn shader_reflection(&self, handle: ShaderHandle) -> Result<shader::ShaderReflection, Error>;
+
+    /// Whether the fragment stage of the shader declares `layout(early_fragment_tests) in;`, in
+    /// which case depth and stencil tests run, and depth is written, before the fragment shader.
+    ///
+    /// The declaration can't be queried from the driver, it is detected in the stage sources.
+    /// Shaders created from binaries always report `false`.
+    ///
+    /// # Errors
+    /// `ResourceNotFound`: When the handle doesn't point to a shader program.
+    fn shader_uses_early_z(&self, handle: ShaderHandle) -> Result<bool, Error>;
+
+    /// Assigns the uniform block of the shader to the binding point, so that shaders sharing a
+    /// block, like the camera, read it from the same buffer bound with `bind_buffer_range`.
+    ///
+    /// Overrides the binding from the `layout(binding = ...)` qualifier of the block.
+    ///
+    /// # Errors
+    /// `ResourceNotFound`: When the handle doesn't point to a shader program.
+    /// `InvalidOperation`: When the shader has no active block with the name, or the binding
+    /// point exceeds the number of uniform buffer bindings.
+    /// `ConversionError`: When the name contains a nul byte.
+    fn set_uniform_block_binding(
+        &mut self,
+        shader: ShaderHandle,
+        block_name: &str,
+        binding: u32,
+    ) -> Result<(), Error>;
+
+    /// Retrieves the binary of a linked shader program and its format, to cache it on disk and
+    /// skip compiling and linking with `create_shader_from_binary`.
+    ///
+    /// # Errors
+    /// `ResourceNotFound`: When the handle doesn't point to a shader program.
+    /// `InvalidContext`: When the driver doesn't support program binaries.
+    fn get_shader_binary(&self, handle: ShaderHandle) -> Result<(u32, Vec<u8>), Error>;
+
+    /// Creates a shader program from a binary retrieved with `get_shader_binary`.
+    ///
+    /// # Errors
+    /// `FailedToLinkShader`: When the driver rejects the binary, for example because the driver
+    /// version changed. The shader has to be created from its sources again.
+    /// `ConversionError`: When the length of the binary can't be converted into the native type.
+    fn create_shader_from_binary(
+        &mut self,
+        format: u32,
+        binary: &[u8],
+    ) -> Result<ShaderHandle, Error>;
     fn shader_mut(&mut self, handle: ShaderHandle) -> Option<&mut Self::Shader>;
 
     fn layout_mut_and_buffers(
@@ -138,7 +716,84 @@ pub trait Context {
     ) -> (Option<&mut Self::Layout>, Vec<Option<&Self::Buffer>>);
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Primitive {
+    Points,
     Triangles,
     TriangleStrip,
 }
+
+/// Corner of the window that the clip space origin maps to
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ClipOrigin {
+    #[default]
+    LowerLeft,
+    UpperLeft,
+}
+
+/// Optional functionality, depending on the version and the extensions of the context
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Feature {
+    /// Compute shaders and `dispatch_compute_indirect`
+    ComputeShader,
+    /// `set_clip_control`
+    ClipControl,
+    /// Shaders in the SPIR-V binary format
+    SpirV,
+    /// Errors reported through `poll_errors`, only available on debug contexts
+    DebugOutput,
+}
+
+/// Bitwise operation between the fragment color(source) and the color in the render
+/// target(destination)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LogicOp {
+    /// 0
+    Clear,
+    /// 1
+    Set,
+    /// source
+    Copy,
+    /// !source
+    CopyInverted,
+    /// destination
+    Noop,
+    /// !destination
+    Invert,
+    /// source & destination
+    And,
+    /// !(source & destination)
+    Nand,
+    /// source | destination
+    Or,
+    /// !(source | destination)
+    Nor,
+    /// source ^ destination
+    Xor,
+    /// !(source ^ destination)
+    Equiv,
+    /// source & !destination
+    AndReverse,
+    /// !source & destination
+    AndInverted,
+    /// source | !destination
+    OrReverse,
+    /// !source | destination
+    OrInverted,
+}
+
+/// Vertex of a primitive that provides the values of `flat` varyings
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ProvokingVertex {
+    First,
+    #[default]
+    Last,
+}
+
+/// Range of the clip space depth that is mapped to the depth range
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ClipDepth {
+    #[default]
+    NegativeOneToOne,
+    ZeroToOne,
+}
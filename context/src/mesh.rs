@@ -0,0 +1,47 @@
+use crate::{Context, Error, Primitive, RenderTargetHandle, ShaderHandle, VertexLayoutHandle};
+
+/// Everything needed to draw a piece of geometry, except the shader and the render target.
+///
+/// A thin convenience over `Context::draw`, the handles are still owned by the context.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct Mesh {
+    pub layout: VertexLayoutHandle,
+    pub primitive: Primitive,
+    /// First vertex to draw
+    pub start: usize,
+    /// Number of vertices to draw
+    pub count: usize,
+}
+
+impl Mesh {
+    /// Creates a mesh drawing `count` vertices, starting with the first one.
+    #[must_use]
+    pub const fn new(layout: VertexLayoutHandle, primitive: Primitive, count: usize) -> Self {
+        Self {
+            layout,
+            primitive,
+            start: 0,
+            count,
+        }
+    }
+
+    /// Draws the mesh with the shader onto the render target.
+    ///
+    /// # Errors
+    /// See `Context::draw`.
+    pub fn draw(
+        &self,
+        ctx: &mut impl Context,
+        target: RenderTargetHandle,
+        shader: ShaderHandle,
+    ) -> Result<(), Error> {
+        ctx.draw(
+            target,
+            self.primitive,
+            shader,
+            self.layout,
+            self.start,
+            self.count,
+        )
+    }
+}
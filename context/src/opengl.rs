@@ -1,4 +1,6 @@
 mod buffer;
+mod fence;
+mod query;
 mod render_target;
 mod shader;
 mod stage;
@@ -7,18 +9,30 @@ mod vertex_layout;
 mod gl43_core;
 
 use crate::{
-    buffer::FlatData, error::Error, handle, BufferHandle, RenderTargetHandle, ShaderHandle,
-    StageHandle, VertexLayoutHandle,
+    buffer::FlatData, error::Error, handle, BarrierFlags, BufferHandle, ClipDepth, ClipOrigin,
+    DrawCommand, Feature, FenceHandle, LogicOp, PixelReadHandle, ProvokingVertex, QueryHandle,
+    RenderPass, RenderTargetHandle, ShaderHandle, StageHandle, VertexLayoutHandle,
 };
 
 use gl43_core as gl;
 
-use cac_core::{gen_vec::GenVec, math::URect};
+use cac_core::{
+    gen_vec::GenVec,
+    math::{Mat4, Origin, URect},
+};
+use std::{cell::Cell, collections::HashSet};
 
 thread_local! {
     static ERROR_LOGS: Vec<String> = Vec::new();
 }
 
+//enums of functions that are not part of the 4.3 core profile
+const NEGATIVE_ONE_TO_ONE: gl::types::GLenum = 0x935E;
+const ZERO_TO_ONE: gl::types::GLenum = 0x935F;
+
+type ClipControlFn = extern "system" fn(gl::types::GLenum, gl::types::GLenum);
+type GetGraphicsResetStatusFn = extern "system" fn() -> gl::types::GLenum;
+
 pub trait GLContext {
     fn swap_buffers(&mut self);
     fn get_proc_address(&mut self, name: &'static str) -> *const std::ffi::c_void;
@@ -30,6 +44,9 @@ struct Resources {
     stages: GenVec<handle::Stage, stage::Native>,
     shaders: GenVec<handle::Shader, shader::Native>,
     render_targets: GenVec<handle::RenderTarget, render_target::Native>,
+    queries: GenVec<handle::Query, query::Native>,
+    pixel_reads: GenVec<handle::PixelRead, render_target::PixelRead>,
+    fences: GenVec<handle::Fence, fence::Native>,
 }
 
 impl Resources {
@@ -40,6 +57,9 @@ impl Resources {
             stages: GenVec::with_capacity(capacity),
             shaders: GenVec::with_capacity(capacity),
             render_targets: GenVec::with_capacity(capacity),
+            queries: GenVec::with_capacity(capacity),
+            pixel_reads: GenVec::with_capacity(capacity),
+            fences: GenVec::with_capacity(capacity),
         }
     }
 
@@ -48,16 +68,165 @@ impl Resources {
     }
 }
 
+/// Implementation dependent limits, queried once on creation
+struct Limits {
+    uniform_offset_alignment: usize,
+    storage_offset_alignment: usize,
+    /// Number of uniform buffer binding points
+    uniform_bindings: usize,
+}
+
+impl Limits {
+    fn load() -> Self {
+        let get = |name| {
+            let mut value = 0;
+            unsafe { gl::GetIntegerv(name, &mut value) };
+            usize::try_from(value).unwrap_or(1).max(1)
+        };
+
+        Self {
+            uniform_offset_alignment: get(gl::UNIFORM_BUFFER_OFFSET_ALIGNMENT),
+            storage_offset_alignment: get(gl::SHADER_STORAGE_BUFFER_OFFSET_ALIGNMENT),
+            uniform_bindings: get(gl::MAX_UNIFORM_BUFFER_BINDINGS),
+        }
+    }
+}
+
+/// Functions that are not part of the 4.3 core profile.
+/// They are only loaded when the version of the context supports them.
+#[derive(Default)]
+struct Extensions {
+    clip_control: Option<ClipControlFn>,
+    get_graphics_reset_status: Option<GetGraphicsResetStatusFn>,
+    /// Names of the extensions reported by the context, like `GL_ARB_gl_spirv`
+    names: HashSet<String>,
+}
+
+impl Extensions {
+    fn load(context: &mut impl GLContext, version: (i32, i32)) -> Self {
+        let mut load = |name, required: (i32, i32)| {
+            if version >= required {
+                Some(context.get_proc_address(name)).filter(|f| !f.is_null())
+            } else {
+                None
+            }
+        };
+
+        Self {
+            clip_control: load("glClipControl", (4, 5)).map(|f| unsafe {
+                std::mem::transmute::<*const std::ffi::c_void, ClipControlFn>(f)
+            }),
+            get_graphics_reset_status: load("glGetGraphicsResetStatus", (4, 5)).map(|f| unsafe {
+                std::mem::transmute::<*const std::ffi::c_void, GetGraphicsResetStatusFn>(f)
+            }),
+            names: Self::load_names(),
+        }
+    }
+
+    fn load_names() -> HashSet<String> {
+        let mut count = 0;
+        unsafe { gl::GetIntegerv(gl::NUM_EXTENSIONS, &mut count) };
+
+        (0..count.max(0).cast_unsigned())
+            .filter_map(|index| {
+                let name = unsafe { gl::GetStringi(gl::EXTENSIONS, index) };
+                (!name.is_null()).then(|| {
+                    unsafe { std::ffi::CStr::from_ptr(name.cast()) }
+                        .to_string_lossy()
+                        .into_owned()
+                })
+            })
+            .collect()
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    /// SPIR-V shaders are core since 4.6, older versions need `GL_ARB_gl_spirv`
+    fn spirv(&self, version: (i32, i32)) -> bool {
+        version >= (4, 6) || self.has("GL_ARB_gl_spirv")
+    }
+}
+
 #[derive(Default)]
 struct State {
     pub bound_layout: Option<VertexLayoutHandle>,
     pub bound_shader: Option<ShaderHandle>,
     pub bound_render_target: Option<RenderTargetHandle>,
+    /// Viewport of `Context::push_viewport`, which replaces the one of the bound render target
+    pub pushed_viewport: Option<URect>,
+    pub active_query: Option<QueryHandle>,
+
+    pub point_coord_origin: Origin,
+    pub program_point_size: bool,
+    pub alpha_to_coverage: bool,
+    pub depth_test: DepthTest,
+    pub seamless_cubemaps: bool,
+    pub sample_shading: Option<f32>,
+    pub clip_control: (ClipOrigin, ClipDepth),
+    pub depth_range: DepthRange,
+    pub provoking_vertex: ProvokingVertex,
+    pub polygon_offset: (f32, f32),
+    pub logic_op: Option<LogicOp>,
+    pub scissor_test: ScissorTest,
+}
+
+/// Near and far of the window depth range
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct DepthRange(f32, f32);
+
+impl Default for DepthRange {
+    fn default() -> Self {
+        Self(0.0, 1.0)
+    }
+}
+
+/// Whether fragments behind the stored depth are discarded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct DepthTest(bool);
+
+/// Whether draws and clears are limited to the viewport of the render target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ScissorTest(bool);
+
+impl Default for ScissorTest {
+    fn default() -> Self {
+        Self(true)
+    }
 }
 
 impl State {
-    pub fn reset(&mut self) {
+    pub fn reset(&mut self, extensions: &Extensions) {
         *self = Self::default();
+        self.apply_render_state(extensions);
+    }
+
+    /// Pushes the tracked render state to the context, so that both are in sync again.
+    fn apply_render_state(&self, extensions: &Extensions) {
+        unsafe {
+            gl::PointParameteri(
+                gl::POINT_SPRITE_COORD_ORIGIN,
+                point_coord_origin(self.point_coord_origin),
+            );
+        }
+        set_capability(gl::PROGRAM_POINT_SIZE, self.program_point_size);
+        set_capability(gl::SAMPLE_ALPHA_TO_COVERAGE, self.alpha_to_coverage);
+        set_capability(gl::DEPTH_TEST, self.depth_test.0);
+        set_capability(gl::TEXTURE_CUBE_MAP_SEAMLESS, self.seamless_cubemaps);
+        apply_sample_shading(self.sample_shading);
+
+        let DepthRange(near, far) = self.depth_range;
+        unsafe { gl::DepthRangef(near, far) }
+        unsafe { gl::ProvokingVertex(self.provoking_vertex.into()) }
+        apply_polygon_offset(self.polygon_offset);
+        apply_logic_op(self.logic_op);
+        set_capability(gl::SCISSOR_TEST, self.scissor_test.0);
+
+        if let Some(clip_control) = extensions.clip_control {
+            let (origin, depth) = self.clip_control;
+            clip_control(origin.into(), depth.into());
+        }
     }
     pub fn bind_render_target(
         &mut self,
@@ -68,6 +237,9 @@ impl State {
             self.bound_render_target = Some(render_rarget);
             if let Some(rt) = resources.render_targets.get_mut(render_rarget) {
                 rt.bind()?;
+                if let Some(viewport) = self.pushed_viewport {
+                    render_target::apply_viewport(viewport)?;
+                }
             } else {
                 return Err(Error::ResourceNotFound);
             }
@@ -117,14 +289,42 @@ impl State {
         self.bind_render_target(resources, render_rarget)?;
         self.bind_layout(resources, layout)?;
         self.bind_shader(resources, shader)?;
+
+        #[cfg(debug_assertions)]
+        Self::validate_layout_buffers(resources, layout);
+
         Ok(())
     }
+
+    /// Warns about buffers that were deleted while the layout still references them.
+    #[cfg(debug_assertions)]
+    fn validate_layout_buffers(resources: &Resources, handle: VertexLayoutHandle) {
+        let Some(layout) = resources.layouts.get(handle) else {
+            return;
+        };
+
+        for buffer in layout
+            .buffers
+            .iter()
+            .filter(|b| resources.buffers.get(**b).is_none())
+        {
+            log::warn!(
+                "vertex layout {handle:?} (id {}) references the deleted buffer {buffer:?}",
+                layout.id
+            );
+        }
+    }
 }
 
 pub struct Context<C: GLContext> {
     gl_context: C,
     resources: Resources,
     state: State,
+    extensions: Extensions,
+    limits: Limits,
+    version: (i32, i32),
+    /// Whether the context is a debug context, which reports errors to the error log
+    debug_output: bool,
 
     //Boxing the collection is fine in this case, because it provides a stable adress to the
     //collection, that can be send over FFI.
@@ -132,11 +332,24 @@ pub struct Context<C: GLContext> {
     error_log: Box<Vec<String>>,
 
     viewport: URect,
+    viewport_stack: Vec<URect>,
+    /// Default framebuffer, recreated with the same handle on reset
+    screen_target: RenderTargetHandle,
+
+    /// Bound for draws without a layout
+    empty_layout: vertex_layout::Native,
+    /// Created with the first draw constants
+    draw_constants: Option<buffer::ConstantRing>,
+    /// Latched once the driver reports a graphics reset, a lost context doesn't come back
+    lost: Cell<bool>,
 }
 
 impl<C: GLContext> Context<C> {
     /// Creates an OpenGL 4.3 context.
     ///
+    /// Errors are only reported through `poll_errors` when the context was created with the
+    /// debug flag, otherwise the debug output stays disabled.
+    ///
     /// # Errors
     /// `Error::InvalidContext`: When the context fails to load the function pointers or is using
     /// an unsupported version(< 4.3 or a non-existant version).
@@ -190,22 +403,47 @@ impl<C: GLContext> Context<C> {
             }
         };
 
+        let mut resources = Resources::with_capacity(10);
+        let screen_target = insert_screen_target(&mut resources, viewport);
+
         let mut ctx = Self {
             gl_context: context,
             error_log: Box::default(),
             viewport,
-            resources: Resources::with_capacity(10),
+            viewport_stack: Vec::new(),
+            screen_target,
+            empty_layout: vertex_layout::Native::empty(),
+            draw_constants: None,
+            lost: Cell::new(false),
+            resources,
             state: State::default(),
+            extensions: Extensions::default(),
+            limits: Limits::load(),
+            version,
+            debug_output: false,
         };
+        ctx.extensions = Extensions::load(&mut ctx.gl_context, version);
+
+        let mut flags = 0;
+        unsafe { gl::GetIntegerv(gl::CONTEXT_FLAGS, &mut flags) };
+        ctx.debug_output = flags.cast_unsigned() & gl::CONTEXT_FLAG_DEBUG_BIT != 0;
+
+        if ctx.debug_output {
+            unsafe {
+                gl::Enable(gl::DEBUG_OUTPUT);
+                gl::DebugMessageCallback(
+                    Some(debug_callback),
+                    std::ptr::addr_of_mut!(*ctx.error_log).cast(),
+                );
+            }
+        } else {
+            log::warn!("not a debug context, errors are not reported through poll_errors");
+        }
 
         unsafe {
-            gl::Enable(gl::DEBUG_OUTPUT);
-            gl::DebugMessageCallback(
-                Some(debug_callback),
-                std::ptr::addr_of_mut!(*ctx.error_log).cast(),
-            );
-
             gl::Enable(gl::SCISSOR_TEST);
+            //rows of read pixels are tightly packed, like in images
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
         }
 
         Ok(ctx)
@@ -214,6 +452,36 @@ impl<C: GLContext> Context<C> {
     pub fn raw_context(&mut self) -> &mut C {
         &mut self.gl_context
     }
+
+    /// Queries the reset status, if `glGetGraphicsResetStatus` is available, and latches a loss.
+    ///
+    /// Called once per frame by `update`, instead of on every call that checks for a loss.
+    /// Contexts without robust access never report a reset, so they're never considered lost.
+    fn poll_reset_status(&self) {
+        if !self.lost.get() {
+            let status = self
+                .extensions
+                .get_graphics_reset_status
+                .map_or(gl::NO_ERROR, |get_status| get_status());
+
+            if status != gl::NO_ERROR {
+                log::error!("context lost, reset status {status:#X}");
+                self.lost.set(true);
+            }
+        }
+    }
+
+    /// Fails once the context is lost, without querying the driver.
+    ///
+    /// # Errors
+    /// `Error::ContextLost`: When `poll_reset_status` found a reset of the context.
+    fn check_lost(&self) -> Result<(), Error> {
+        if self.lost.get() {
+            Err(Error::ContextLost)
+        } else {
+            Ok(())
+        }
+    }
 }
 
 impl<C: GLContext> crate::Context for Context<C> {
@@ -225,6 +493,7 @@ impl<C: GLContext> crate::Context for Context<C> {
 
     fn update(&mut self) {
         self.gl_context.swap_buffers();
+        self.poll_reset_status();
     }
 
     fn poll_errors(&mut self) -> Option<Vec<String>> {
@@ -236,16 +505,255 @@ impl<C: GLContext> crate::Context for Context<C> {
             Some(log)
         }
     }
+
+    fn debug_state_string(&self) -> String {
+        use std::fmt::Write;
+
+        let state = &self.state;
+        let resources = &self.resources;
+        let mut report = String::from("render state:\n");
+
+        let (mut current_program, mut scissor) = (0, [0; 4]);
+        unsafe {
+            gl::GetIntegerv(gl::CURRENT_PROGRAM, &mut current_program);
+            gl::GetIntegerv(gl::SCISSOR_BOX, scissor.as_mut_ptr());
+        }
+
+        let _ = writeln!(report, "  debug output: {}", self.debug_output);
+        let _ = writeln!(report, "  viewport: {:?}", self.viewport);
+        let _ = writeln!(report, "  scissor box: {scissor:?}");
+
+        let shader = state
+            .bound_shader
+            .and_then(|s| resources.shaders.get(s))
+            .map_or_else(|| String::from("none"), |s| format!("program {}", s.id));
+        let _ = writeln!(
+            report,
+            "  shader: {shader} (current program {current_program})"
+        );
+
+        let layout = state
+            .bound_layout
+            .and_then(|l| resources.layouts.get(l))
+            .map_or_else(
+                || String::from("none"),
+                |l| format!("vertex array {}", l.id),
+            );
+        let _ = writeln!(report, "  layout: {layout}");
+
+        let render_target = state
+            .bound_render_target
+            .and_then(|rt| resources.render_targets.get(rt))
+            .map_or_else(
+                || String::from("none"),
+                |rt| format!("viewport {:?}", rt.viewport()),
+            );
+        let _ = writeln!(report, "  render target: {render_target}");
+
+        let _ = writeln!(
+            report,
+            "  point coord origin: {:?}",
+            state.point_coord_origin
+        );
+        let _ = writeln!(report, "  program point size: {}", state.program_point_size);
+        let alpha_to_coverage = unsafe { gl::IsEnabled(gl::SAMPLE_ALPHA_TO_COVERAGE) } == gl::TRUE;
+        let _ = writeln!(
+            report,
+            "  alpha to coverage: {} (enabled {alpha_to_coverage})",
+            state.alpha_to_coverage
+        );
+        let _ = writeln!(report, "  seamless cubemaps: {}", state.seamless_cubemaps);
+        let _ = writeln!(report, "  sample shading: {:?}", state.sample_shading);
+        let _ = writeln!(report, "  clip control: {:?}", state.clip_control);
+        let _ = writeln!(report, "  depth range: {:?}", state.depth_range);
+        let _ = writeln!(report, "  depth test: {}", state.depth_test.0);
+        let _ = writeln!(report, "  provoking vertex: {:?}", state.provoking_vertex);
+        let _ = writeln!(report, "  polygon offset: {:?}", state.polygon_offset);
+        let _ = writeln!(report, "  logic op: {:?}", state.logic_op);
+        let _ = writeln!(report, "  scissor test: {}", state.scissor_test.0);
+
+        for (name, capability) in [
+            ("blend", gl::BLEND),
+            ("depth test", gl::DEPTH_TEST),
+            ("cull face", gl::CULL_FACE),
+            ("multisample", gl::MULTISAMPLE),
+        ] {
+            let enabled = unsafe { gl::IsEnabled(capability) } == gl::TRUE;
+            let _ = writeln!(report, "  {name}: {enabled}");
+        }
+
+        report
+    }
+    fn is_lost(&self) -> bool {
+        self.check_lost().is_err()
+    }
+
     fn reset(&mut self) {
         self.resources.clear();
-        self.state.reset();
+        self.state.reset(&self.extensions);
         self.error_log.clear();
+
+        if let Some(viewport) = self.viewport_stack.first() {
+            self.viewport = *viewport;
+            if let Err(e) = render_target::apply_viewport(self.viewport) {
+                log::error!("failed to restore the viewport {viewport:?}: {e:?}");
+            }
+        }
+        self.viewport_stack.clear();
+
+        //the cleared collection hands out the same first handle again
+        self.screen_target = insert_screen_target(&mut self.resources, self.viewport);
+
+        //the ring survives the reset, but its binding point might have been taken by a deleted
+        //buffer
+        if let Some(ring) = &self.draw_constants {
+            ring.rebind();
+        }
+    }
+
+    fn supports(&self, feature: Feature) -> bool {
+        match feature {
+            Feature::ComputeShader => self.version >= (4, 3),
+            Feature::ClipControl => self.extensions.clip_control.is_some(),
+            Feature::SpirV => self.extensions.spirv(self.version),
+            Feature::DebugOutput => self.debug_output,
+        }
+    }
+
+    fn screen_target(&self) -> RenderTargetHandle {
+        self.screen_target
     }
 
     fn viewport(&self) -> URect {
         self.viewport
     }
 
+    fn push_viewport(&mut self, viewport: URect) -> Result<(), Error> {
+        render_target::apply_viewport(viewport)?;
+        self.viewport_stack.push(self.viewport);
+        self.viewport = viewport;
+
+        //binding a render target applies its own viewport, so it must not be skipped
+        self.state.pushed_viewport = Some(viewport);
+        self.state.bound_render_target = None;
+        Ok(())
+    }
+
+    fn pop_viewport(&mut self) -> Option<URect> {
+        let previous = self.viewport_stack.pop()?;
+        if let Err(e) = render_target::apply_viewport(previous) {
+            log::error!("failed to restore the viewport {previous:?}: {e:?}");
+        }
+
+        //without a pushed viewport, the next draw applies the viewport of its render target
+        self.state.pushed_viewport = (!self.viewport_stack.is_empty()).then_some(previous);
+        self.state.bound_render_target = None;
+        Some(std::mem::replace(&mut self.viewport, previous))
+    }
+
+    fn set_point_coord_origin(&mut self, origin: Origin) {
+        if self.state.point_coord_origin != origin {
+            self.state.point_coord_origin = origin;
+            unsafe {
+                gl::PointParameteri(gl::POINT_SPRITE_COORD_ORIGIN, point_coord_origin(origin));
+            }
+        }
+    }
+
+    fn set_program_point_size(&mut self, enabled: bool) {
+        if self.state.program_point_size != enabled {
+            self.state.program_point_size = enabled;
+            set_capability(gl::PROGRAM_POINT_SIZE, enabled);
+        }
+    }
+
+    fn set_alpha_to_coverage(&mut self, enabled: bool) {
+        if self.state.alpha_to_coverage != enabled {
+            self.state.alpha_to_coverage = enabled;
+            set_capability(gl::SAMPLE_ALPHA_TO_COVERAGE, enabled);
+        }
+    }
+
+    fn set_depth_test(&mut self, enabled: bool) {
+        if self.state.depth_test.0 != enabled {
+            self.state.depth_test = DepthTest(enabled);
+            set_capability(gl::DEPTH_TEST, enabled);
+        }
+    }
+
+    fn set_scissor_test(&mut self, enabled: bool) {
+        if self.state.scissor_test.0 != enabled {
+            self.state.scissor_test = ScissorTest(enabled);
+            set_capability(gl::SCISSOR_TEST, enabled);
+        }
+    }
+
+    fn set_seamless_cubemaps(&mut self, enabled: bool) {
+        if self.state.seamless_cubemaps != enabled {
+            self.state.seamless_cubemaps = enabled;
+            set_capability(gl::TEXTURE_CUBE_MAP_SEAMLESS, enabled);
+        }
+    }
+
+    fn set_sample_shading(&mut self, min_sample_rate: Option<f32>) {
+        let min_sample_rate = min_sample_rate.map(|rate| rate.clamp(0.0, 1.0));
+        if self.state.sample_shading != min_sample_rate {
+            self.state.sample_shading = min_sample_rate;
+            apply_sample_shading(min_sample_rate);
+        }
+    }
+
+    fn memory_barrier(&mut self, barriers: BarrierFlags) {
+        if !barriers.is_empty() {
+            unsafe { gl::MemoryBarrier(barriers.into()) }
+        }
+    }
+
+    fn set_clip_control(&mut self, origin: ClipOrigin, depth: ClipDepth) -> Result<(), Error> {
+        let clip_control = self.extensions.clip_control.ok_or_else(|| {
+            Error::InvalidContext(format!(
+                "clip control requires version 4.5, received {}.{}",
+                self.version.0, self.version.1
+            ))
+        })?;
+
+        if self.state.clip_control != (origin, depth) {
+            self.state.clip_control = (origin, depth);
+            clip_control(origin.into(), depth.into());
+        }
+
+        Ok(())
+    }
+
+    fn set_depth_range(&mut self, near: f32, far: f32) {
+        let range = DepthRange(near.clamp(0.0, 1.0), far.clamp(0.0, 1.0));
+        if self.state.depth_range != range {
+            self.state.depth_range = range;
+            unsafe { gl::DepthRangef(range.0, range.1) }
+        }
+    }
+
+    fn set_provoking_vertex(&mut self, mode: ProvokingVertex) {
+        if self.state.provoking_vertex != mode {
+            self.state.provoking_vertex = mode;
+            unsafe { gl::ProvokingVertex(mode.into()) }
+        }
+    }
+
+    fn set_polygon_offset(&mut self, factor: f32, units: f32) {
+        if self.state.polygon_offset != (factor, units) {
+            self.state.polygon_offset = (factor, units);
+            apply_polygon_offset((factor, units));
+        }
+    }
+
+    fn set_logic_op(&mut self, op: Option<LogicOp>) {
+        if self.state.logic_op != op {
+            self.state.logic_op = op;
+            apply_logic_op(op);
+        }
+    }
+
     fn draw(
         &mut self,
         render_rarget: RenderTargetHandle,
@@ -255,24 +763,304 @@ impl<C: GLContext> crate::Context for Context<C> {
         start: usize,
         count: usize,
     ) -> std::result::Result<(), Error> {
+        self.check_lost()?;
         self.state
             .bind_draw_state(&mut self.resources, render_rarget, layout, shader)?;
+        let (start, count) = draw_range(start, count)?;
 
-        let start = start
-            .try_into()
-            .map_err(|_| Error::ConversionFailed("start wraps around i32"))?;
+        unsafe {
+            gl::DrawArrays(primitive.into(), start, count);
+        }
+
+        Ok(())
+    }
+
+    fn run_pass(&mut self, pass: &RenderPass, draws: &[DrawCommand]) -> Result<(), Error> {
+        self.check_lost()?;
+        self.state
+            .bind_render_target(&mut self.resources, pass.target)?;
+        if pass.clear {
+            crate::Context::clear(self, pass.target)?;
+        }
+
+        let DepthRange(near, far) = self.state.depth_range;
+        if let Some((near, far)) = pass.depth_range {
+            self.set_depth_range(near, far);
+        }
+        let DepthTest(depth_test) = self.state.depth_test;
+        if let Some(enabled) = pass.depth_test {
+            self.set_depth_test(enabled);
+        }
+
+        //the viewport is pushed after binding the target, so it isn't replaced by the target's
+        let mut result = pass
+            .viewport
+            .map_or(Ok(()), |viewport| self.push_viewport(viewport));
+        let pushed = pass.viewport.is_some() && result.is_ok();
+
+        if result.is_ok() {
+            result = draws.iter().try_for_each(|command| {
+                let mesh = command.mesh;
+                crate::Context::draw(
+                    self,
+                    pass.target,
+                    mesh.primitive,
+                    command.shader,
+                    mesh.layout,
+                    mesh.start,
+                    mesh.count,
+                )
+            });
+        }
+
+        if pushed {
+            self.pop_viewport();
+        }
+        self.set_depth_range(near, far);
+        self.set_depth_test(depth_test);
+
+        result
+    }
+
+    fn set_draw_constants<T: FlatData>(&mut self, data: &T) -> Result<(), Error> {
+        self.check_lost()?;
+        let ring = match &mut self.draw_constants {
+            Some(ring) => ring,
+            ring => ring.insert(buffer::ConstantRing::new(
+                self.limits.uniform_offset_alignment,
+            )?),
+        };
+
+        ring.push(data)
+    }
+
+    fn draw_transformed(
+        &mut self,
+        target: RenderTargetHandle,
+        primitive: crate::Primitive,
+        shader: ShaderHandle,
+        layout: VertexLayoutHandle,
+        start: usize,
+        count: usize,
+        transform: Mat4,
+    ) -> Result<(), Error> {
+        self.check_lost()?;
+        self.state
+            .bind_draw_state(&mut self.resources, target, layout, shader)?;
+        let (start, count) = draw_range(start, count)?;
+
+        //the uniform is only changed once the draw is known to go ahead
+        self.resources
+            .shaders
+            .get(shader)
+            .ok_or(Error::ResourceNotFound)?
+            .set_transform(&transform);
+
+        unsafe {
+            gl::DrawArrays(primitive.into(), start, count);
+        }
+
+        Ok(())
+    }
+
+    fn draw_arrays_no_layout(
+        &mut self,
+        target: RenderTargetHandle,
+        primitive: crate::Primitive,
+        shader: ShaderHandle,
+        count: usize,
+    ) -> Result<(), Error> {
+        self.check_lost()?;
+        self.state.bind_render_target(&mut self.resources, target)?;
+        self.state.bind_shader(&mut self.resources, shader)?;
+
+        //the empty layout isn't tracked, so the next draw with a layout binds it again
+        self.state.bound_layout = None;
+        self.empty_layout.bind();
 
         let count = count
             .try_into()
             .map_err(|_| Error::ConversionFailed("count wraps around i32"))?;
 
         unsafe {
-            gl::DrawArrays(primitive.into(), start, count);
+            gl::DrawArrays(primitive.into(), 0, count);
+        }
+
+        Ok(())
+    }
+
+    fn draw_arrays_indirect(
+        &mut self,
+        target: RenderTargetHandle,
+        primitive: crate::Primitive,
+        shader: ShaderHandle,
+        layout: VertexLayoutHandle,
+        indirect_buffer: BufferHandle,
+        offset: usize,
+    ) -> Result<(), Error> {
+        self.check_lost()?;
+        let buffer = self
+            .resources
+            .buffers
+            .get(indirect_buffer)
+            .ok_or(Error::ResourceNotFound)?;
+        validate_indirect_draws(buffer, offset, 1, 0)?;
+        let buffer = buffer.id;
+
+        self.state
+            .bind_draw_state(&mut self.resources, target, layout, shader)?;
+
+        unsafe {
+            gl::BindBuffer(gl::DRAW_INDIRECT_BUFFER, buffer);
+            gl::DrawArraysIndirect(primitive.into(), offset as *const std::ffi::c_void);
+        }
+
+        Ok(())
+    }
+
+    fn multi_draw_indirect(
+        &mut self,
+        target: RenderTargetHandle,
+        primitive: crate::Primitive,
+        shader: ShaderHandle,
+        layout: VertexLayoutHandle,
+        indirect_buffer: BufferHandle,
+        draw_count: usize,
+        stride: usize,
+    ) -> Result<(), Error> {
+        self.check_lost()?;
+        let buffer = self
+            .resources
+            .buffers
+            .get(indirect_buffer)
+            .ok_or(Error::ResourceNotFound)?;
+        validate_indirect_draws(buffer, 0, draw_count, stride)?;
+        let buffer = buffer.id;
+
+        let draw_count = draw_count
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("draw count to GLsizei"))?;
+        let stride = stride
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("indirect stride to GLsizei"))?;
+
+        self.state
+            .bind_draw_state(&mut self.resources, target, layout, shader)?;
+
+        unsafe {
+            gl::BindBuffer(gl::DRAW_INDIRECT_BUFFER, buffer);
+            gl::MultiDrawArraysIndirect(primitive.into(), std::ptr::null(), draw_count, stride);
         }
 
         Ok(())
     }
 
+    fn dispatch_compute_indirect(
+        &mut self,
+        shader: ShaderHandle,
+        indirect_buffer: BufferHandle,
+        offset: usize,
+    ) -> Result<(), Error> {
+        self.check_lost()?;
+        self.state.bind_shader(&mut self.resources, shader)?;
+
+        let buffer = self
+            .resources
+            .buffers
+            .get(indirect_buffer)
+            .ok_or(Error::ResourceNotFound)?;
+        validate_indirect_dispatch(buffer, offset)?;
+
+        let offset = offset
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("offset wraps around isize"))?;
+
+        unsafe {
+            gl::BindBuffer(gl::DISPATCH_INDIRECT_BUFFER, buffer.id);
+            gl::DispatchComputeIndirect(offset);
+        }
+
+        Ok(())
+    }
+
+    /*******************************
+     *          QUERY
+     *******************************/
+    fn begin_query(&mut self, kind: crate::query::Kind) -> Result<(), Error> {
+        self.check_lost()?;
+        if self.state.active_query.is_some() {
+            return Err(Error::InvalidOperation("another query is still active"));
+        }
+
+        let query = query::Native::begin(kind);
+        self.state.active_query = Some(self.resources.queries.insert(query));
+
+        Ok(())
+    }
+
+    fn end_query(&mut self) -> Result<QueryHandle, Error> {
+        self.check_lost()?;
+        let handle = self
+            .state
+            .active_query
+            .take()
+            .ok_or(Error::InvalidOperation("no active query"))?;
+
+        self.resources
+            .queries
+            .get(handle)
+            .ok_or(Error::ResourceNotFound)?
+            .end();
+
+        Ok(handle)
+    }
+
+    fn query_result(&self, handle: QueryHandle) -> Option<u64> {
+        if self.state.active_query == Some(handle) {
+            return None;
+        }
+
+        self.resources.queries.get(handle)?.result()
+    }
+
+    fn delete_query(&mut self, handle: QueryHandle) -> Result<(), Error> {
+        if self.state.active_query == Some(handle) {
+            return Err(Error::InvalidOperation("the query is still active"));
+        }
+
+        self.resources
+            .queries
+            .remove(handle)
+            .map(drop)
+            .ok_or(Error::ResourceNotFound)
+    }
+
+    fn insert_fence(&mut self) -> Result<FenceHandle, Error> {
+        self.check_lost()?;
+        Ok(self.resources.fences.insert(fence::Native::insert()?))
+    }
+
+    fn wait_fence(
+        &self,
+        fence: FenceHandle,
+        timeout_ns: u64,
+    ) -> Result<crate::fence::Status, Error> {
+        self.check_lost()?;
+        self.resources
+            .fences
+            .get(fence)
+            .ok_or(Error::ResourceNotFound)?
+            .wait(timeout_ns)
+    }
+
+    fn delete_fence(&mut self, fence: FenceHandle) -> Result<(), Error> {
+        self.resources
+            .fences
+            .remove(fence)
+            .map(drop)
+            .ok_or(Error::ResourceNotFound)
+    }
+
     /*******************************
      *          RENDER TARGET
      *******************************/
@@ -280,6 +1068,7 @@ impl<C: GLContext> crate::Context for Context<C> {
         &mut self,
         render_target: crate::RenderTarget,
     ) -> Result<crate::RenderTargetHandle, Error> {
+        self.check_lost()?;
         let rt = Self::RenderTarget::new(render_target);
         Ok(self.resources.render_targets.insert(rt))
     }
@@ -292,10 +1081,12 @@ impl<C: GLContext> crate::Context for Context<C> {
         &mut self,
         handle: crate::RenderTargetHandle,
     ) -> Option<&mut Self::RenderTarget> {
-        if self
-            .state
-            .bind_render_target(&mut self.resources, handle)
-            .is_ok()
+        //the screen target keeps the state it was created with
+        if handle != self.screen_target
+            && self
+                .state
+                .bind_render_target(&mut self.resources, handle)
+                .is_ok()
         {
             self.resources.render_targets.get_mut(handle)
         } else {
@@ -303,17 +1094,98 @@ impl<C: GLContext> crate::Context for Context<C> {
         }
     }
 
+    fn clear(&mut self, target: RenderTargetHandle) -> Result<(), Error> {
+        self.check_lost()?;
+        self.state.bind_render_target(&mut self.resources, target)?;
+        let target = self
+            .resources
+            .render_targets
+            .get_mut(target)
+            .ok_or(Error::ResourceNotFound)?;
+        crate::render_target::Native::clear(target);
+        Ok(())
+    }
+
+    fn clear_depth(&mut self, target: RenderTargetHandle) -> Result<(), Error> {
+        self.check_lost()?;
+        self.state.bind_render_target(&mut self.resources, target)?;
+        render_target::clear_depth();
+        Ok(())
+    }
+
+    fn clear_color_only(&mut self, target: RenderTargetHandle) -> Result<(), Error> {
+        self.check_lost()?;
+        self.state.bind_render_target(&mut self.resources, target)?;
+        self.resources
+            .render_targets
+            .get(target)
+            .ok_or(Error::ResourceNotFound)?
+            .clear_color_only();
+        Ok(())
+    }
+
+    fn read_pixels_async(
+        &mut self,
+        target: RenderTargetHandle,
+        format: cac_core::image::Format,
+        rect: URect,
+    ) -> Result<PixelReadHandle, Error> {
+        self.check_lost()?;
+        self.state.bind_render_target(&mut self.resources, target)?;
+
+        let read = render_target::PixelRead::new(format, rect)?;
+        Ok(self.resources.pixel_reads.insert(read))
+    }
+
+    fn pixel_read_result(
+        &mut self,
+        handle: PixelReadHandle,
+    ) -> Result<Option<cac_core::image::Image>, Error> {
+        self.check_lost()?;
+        let image = self
+            .resources
+            .pixel_reads
+            .get(handle)
+            .ok_or(Error::ResourceNotFound)?
+            .try_read()?;
+
+        if image.is_some() {
+            self.resources.pixel_reads.remove(handle);
+        }
+
+        Ok(image)
+    }
+
     /*******************************
      *          BUFFER
      *******************************/
-    fn create_buffer<T: FlatData>(
+    fn create_buffer<T: FlatData + 'static>(
         &mut self,
         buffer: &crate::Buffer<T>,
     ) -> Result<BufferHandle, Error> {
+        self.check_lost()?;
         let buffer = Self::Buffer::new(buffer)?;
         Ok(self.resources.buffers.insert(buffer))
     }
 
+    fn create_buffer_from_iter<T: FlatData + 'static>(
+        &mut self,
+        items: impl ExactSizeIterator<Item = T>,
+        access: crate::buffer::Access,
+        usage: crate::buffer::Usage,
+    ) -> Result<BufferHandle, Error> {
+        self.check_lost()?;
+        let buffer = Self::Buffer::from_iter(
+            &crate::Buffer {
+                access,
+                usage,
+                ..crate::Buffer::new()
+            },
+            items,
+        )?;
+        Ok(self.resources.buffers.insert(buffer))
+    }
+
     fn buffer(&self, handle: BufferHandle) -> Option<&Self::Buffer> {
         self.resources.buffers.get(handle)
     }
@@ -322,10 +1194,118 @@ impl<C: GLContext> crate::Context for Context<C> {
         self.resources.buffers.get_mut(handle)
     }
 
+    fn buffer_element_size(&self, handle: BufferHandle) -> Option<usize> {
+        self.resources
+            .buffers
+            .get(handle)
+            .map(crate::buffer::Native::element_size)
+    }
+
+    fn orphan_buffer(&mut self, handle: BufferHandle) -> Result<(), Error> {
+        self.check_lost()?;
+        self.resources
+            .buffers
+            .get_mut(handle)
+            .ok_or(Error::ResourceNotFound)?
+            .orphan();
+
+        Ok(())
+    }
+
+    fn map_buffer_range(
+        &mut self,
+        handle: BufferHandle,
+        offset: usize,
+        length: usize,
+        flags: crate::buffer::MapFlags,
+    ) -> Result<crate::buffer::Mapping<'_>, Error> {
+        self.check_lost()?;
+        self.resources
+            .buffers
+            .get_mut(handle)
+            .ok_or(Error::ResourceNotFound)?
+            .map_range(offset, length, flags)
+    }
+
+    fn bind_buffer_range(
+        &mut self,
+        handle: BufferHandle,
+        binding: u32,
+        offset: usize,
+        size: usize,
+        kind: crate::buffer::Kind,
+    ) -> Result<(), Error> {
+        self.check_lost()?;
+        let alignment = match kind {
+            crate::buffer::Kind::Uniform => self.limits.uniform_offset_alignment,
+            crate::buffer::Kind::Storage => self.limits.storage_offset_alignment,
+            _ => {
+                return Err(Error::InvalidOperation(
+                    "only uniform and storage buffers have binding points",
+                ))
+            }
+        };
+
+        if !offset.is_multiple_of(alignment) {
+            return Err(Error::InvalidOperation(
+                "offset isn't a multiple of the buffer offset alignment",
+            ));
+        }
+
+        if size == 0 {
+            return Err(Error::InvalidOperation("bound range is empty"));
+        }
+
+        let buffer = self
+            .resources
+            .buffers
+            .get(handle)
+            .ok_or(Error::ResourceNotFound)?;
+
+        let in_bounds = offset
+            .checked_add(size)
+            .is_some_and(|end| end <= crate::buffer::Native::size(buffer));
+        if !in_bounds {
+            return Err(Error::InvalidOperation(
+                "bound range is outside of the buffer",
+            ));
+        }
+
+        let gl_offset = offset
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("range offset to GLintptr"))?;
+
+        let gl_size = size
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("range size to GLsizeiptr"))?;
+
+        unsafe { gl::BindBufferRange(kind.into(), binding, buffer.id, gl_offset, gl_size) };
+
+        Ok(())
+    }
+
+    fn delete_buffer(&mut self, handle: BufferHandle) -> Result<(), Error> {
+        self.resources
+            .buffers
+            .remove(handle)
+            .map(drop)
+            .ok_or(Error::ResourceNotFound)
+    }
+
+    fn set_buffer_name(&mut self, handle: BufferHandle, name: &str) -> Result<(), Error> {
+        self.resources
+            .buffers
+            .get_mut(handle)
+            .ok_or(Error::ResourceNotFound)?
+            .name = Some(String::from(name));
+        Ok(())
+    }
+
     /*******************************
      *          VertexLayout
      *******************************/
     fn create_layout(&mut self, layout: &crate::VertexLayout) -> Result<VertexLayoutHandle, Error> {
+        self.check_lost()?;
         let layout = vertex_layout::Native::new(layout, &self.resources.buffers)?;
         let handle = self.resources.layouts.insert(layout);
 
@@ -345,6 +1325,15 @@ impl<C: GLContext> crate::Context for Context<C> {
         }
     }
 
+    fn set_layout_name(&mut self, handle: VertexLayoutHandle, name: &str) -> Result<(), Error> {
+        self.resources
+            .layouts
+            .get_mut(handle)
+            .ok_or(Error::ResourceNotFound)?
+            .name = Some(String::from(name));
+        Ok(())
+    }
+
     fn layout_mut_and_buffers(
         &mut self,
         handle: VertexLayoutHandle,
@@ -369,6 +1358,7 @@ impl<C: GLContext> crate::Context for Context<C> {
      *******************************/
 
     fn create_stage(&mut self, shader: crate::shader::Stage) -> Result<StageHandle, Error> {
+        self.check_lost()?;
         let stage = Self::Stage::new(shader)?;
         Ok(self.resources.stages.insert(stage))
     }
@@ -377,7 +1367,17 @@ impl<C: GLContext> crate::Context for Context<C> {
         self.resources.stages.get(handle)
     }
 
+    fn set_stage_name(&mut self, handle: StageHandle, name: &str) -> Result<(), Error> {
+        self.resources
+            .stages
+            .get_mut(handle)
+            .ok_or(Error::ResourceNotFound)?
+            .name = Some(String::from(name));
+        Ok(())
+    }
+
     fn create_shader(&mut self, shader: crate::shader::Shader) -> Result<ShaderHandle, Error> {
+        self.check_lost()?;
         let shader = Self::Shader::new(shader, &self.resources.stages)?;
 
         Ok(self.resources.shaders.insert(shader))
@@ -387,6 +1387,74 @@ impl<C: GLContext> crate::Context for Context<C> {
         self.resources.shaders.get(handle)
     }
 
+    fn set_shader_name(&mut self, handle: ShaderHandle, name: &str) -> Result<(), Error> {
+        self.resources
+            .shaders
+            .get_mut(handle)
+            .ok_or(Error::ResourceNotFound)?
+            .name = Some(String::from(name));
+        Ok(())
+    }
+
+    fn set_uniform_block_binding(
+        &mut self,
+        shader: ShaderHandle,
+        block_name: &str,
+        binding: u32,
+    ) -> Result<(), Error> {
+        self.check_lost()?;
+        if usize::try_from(binding).map_or(true, |b| b >= self.limits.uniform_bindings) {
+            return Err(Error::InvalidOperation(
+                "binding is outside of the uniform buffer binding points",
+            ));
+        }
+
+        self.resources
+            .shaders
+            .get(shader)
+            .ok_or(Error::ResourceNotFound)?
+            .set_uniform_block_binding(block_name, binding)
+    }
+
+    fn get_shader_binary(&self, handle: ShaderHandle) -> Result<(u32, Vec<u8>), Error> {
+        self.check_lost()?;
+        self.resources
+            .shaders
+            .get(handle)
+            .ok_or(Error::ResourceNotFound)?
+            .binary()
+    }
+
+    fn create_shader_from_binary(
+        &mut self,
+        format: u32,
+        binary: &[u8],
+    ) -> Result<ShaderHandle, Error> {
+        self.check_lost()?;
+        let shader = Self::Shader::from_binary(format, binary)?;
+
+        Ok(self.resources.shaders.insert(shader))
+    }
+
+    fn shader_reflection(
+        &self,
+        handle: ShaderHandle,
+    ) -> Result<crate::shader::ShaderReflection, Error> {
+        self.resources
+            .shaders
+            .get(handle)
+            .ok_or(Error::ResourceNotFound)?
+            .reflect()
+    }
+
+    fn shader_uses_early_z(&self, handle: ShaderHandle) -> Result<bool, Error> {
+        self.resources
+            .shaders
+            .get(handle)
+            .map(shader::Native::early_fragment_tests)
+            .ok_or(Error::ResourceNotFound)
+    }
+
     fn shader_mut(&mut self, handle: ShaderHandle) -> Option<&mut Self::Shader> {
         if self.state.bind_shader(&mut self.resources, handle).is_ok() {
             self.resources.shaders.get_mut(handle)
@@ -398,15 +1466,232 @@ impl<C: GLContext> crate::Context for Context<C> {
 
 impl From<crate::Primitive> for gl::types::GLenum {
     fn from(value: crate::Primitive) -> Self {
-        use gl::{TRIANGLES, TRIANGLE_STRIP};
+        use gl::{POINTS, TRIANGLES, TRIANGLE_STRIP};
 
         match value {
+            crate::Primitive::Points => POINTS,
             crate::Primitive::Triangles => TRIANGLES,
             crate::Primitive::TriangleStrip => TRIANGLE_STRIP,
         }
     }
 }
 
+/// Adds the default framebuffer at the viewport as a render target without clear color.
+fn insert_screen_target(resources: &mut Resources, viewport: URect) -> RenderTargetHandle {
+    resources
+        .render_targets
+        .insert(render_target::Native::new(crate::RenderTarget {
+            clear_color: None,
+            viewport,
+        }))
+}
+
+/// Converts the first vertex and the vertex count of a draw into the native values.
+fn draw_range(start: usize, count: usize) -> Result<(gl::types::GLint, gl::types::GLsizei), Error> {
+    let start = start
+        .try_into()
+        .map_err(|_| Error::ConversionFailed("start wraps around i32"))?;
+
+    let count = count
+        .try_into()
+        .map_err(|_| Error::ConversionFailed("count wraps around i32"))?;
+
+    Ok((start, count))
+}
+
+/// Size in bytes of the parameters of a single indirect draw
+const INDIRECT_DRAW_SIZE: usize = 16;
+
+/// Checks that the parameters of `draw_count` indirect draws, starting at the offset and `stride`
+/// bytes apart, are aligned and inside the buffer. A stride of 0 means tightly packed.
+fn validate_indirect_draws(
+    buffer: &buffer::Native,
+    offset: usize,
+    draw_count: usize,
+    stride: usize,
+) -> Result<(), Error> {
+    if !offset.is_multiple_of(4) {
+        return Err(Error::InvalidOperation(
+            "indirect offset has to be a multiple of 4",
+        ));
+    }
+
+    if stride != 0 && (stride < INDIRECT_DRAW_SIZE || !stride.is_multiple_of(4)) {
+        return Err(Error::InvalidOperation(
+            "indirect stride has to be 0 or a multiple of 4 of at least 16",
+        ));
+    }
+
+    let Some(last) = draw_count.checked_sub(1) else {
+        return Ok(());
+    };
+    let stride = if stride == 0 {
+        INDIRECT_DRAW_SIZE
+    } else {
+        stride
+    };
+    let end = last
+        .checked_mul(stride)
+        .and_then(|last| last.checked_add(offset))
+        .and_then(|start| start.checked_add(INDIRECT_DRAW_SIZE));
+
+    if end.is_some_and(|end| end <= crate::buffer::Native::size(buffer)) {
+        Ok(())
+    } else {
+        Err(Error::InvalidOperation(
+            "indirect draws are outside of the buffer",
+        ))
+    }
+}
+
+/// Size in bytes of the parameters of an indirect dispatch, three u32 work group counts
+const INDIRECT_DISPATCH_SIZE: usize = 12;
+
+/// Checks that the parameters of an indirect dispatch at the offset are aligned and inside the
+/// buffer.
+fn validate_indirect_dispatch(buffer: &buffer::Native, offset: usize) -> Result<(), Error> {
+    if !offset.is_multiple_of(4) {
+        return Err(Error::InvalidOperation(
+            "indirect offset has to be a multiple of 4",
+        ));
+    }
+
+    let end = offset.checked_add(INDIRECT_DISPATCH_SIZE);
+    if end.is_some_and(|end| end <= crate::buffer::Native::size(buffer)) {
+        Ok(())
+    } else {
+        Err(Error::InvalidOperation(
+            "indirect dispatch is outside of the buffer",
+        ))
+    }
+}
+
+/// Formats the optional name of a resource for the logs.
+fn display_name(name: Option<&str>) -> String {
+    name.map_or_else(String::new, |name| format!(" \"{name}\""))
+}
+
+fn set_capability(capability: gl::types::GLenum, enabled: bool) {
+    unsafe {
+        if enabled {
+            gl::Enable(capability);
+        } else {
+            gl::Disable(capability);
+        }
+    }
+}
+
+fn apply_sample_shading(min_sample_rate: Option<f32>) {
+    set_capability(gl::SAMPLE_SHADING, min_sample_rate.is_some());
+    if let Some(rate) = min_sample_rate {
+        unsafe { gl::MinSampleShading(rate) }
+    }
+}
+
+fn apply_polygon_offset((factor, units): (f32, f32)) {
+    set_capability(gl::POLYGON_OFFSET_FILL, factor != 0.0 || units != 0.0);
+    unsafe { gl::PolygonOffset(factor, units) }
+}
+
+fn apply_logic_op(op: Option<LogicOp>) {
+    set_capability(gl::COLOR_LOGIC_OP, op.is_some());
+    if let Some(op) = op {
+        unsafe { gl::LogicOp(op.into()) }
+    }
+}
+
+#[allow(clippy::cast_possible_wrap)] //the enums are well within the range of GLint
+const fn point_coord_origin(origin: Origin) -> gl::types::GLint {
+    match origin {
+        Origin::BottomLeft => gl::LOWER_LEFT as gl::types::GLint,
+        Origin::TopLeft => gl::UPPER_LEFT as gl::types::GLint,
+    }
+}
+
+impl From<ClipOrigin> for gl::types::GLenum {
+    fn from(value: ClipOrigin) -> Self {
+        match value {
+            ClipOrigin::LowerLeft => gl::LOWER_LEFT,
+            ClipOrigin::UpperLeft => gl::UPPER_LEFT,
+        }
+    }
+}
+
+impl From<ClipDepth> for gl::types::GLenum {
+    fn from(value: ClipDepth) -> Self {
+        match value {
+            ClipDepth::NegativeOneToOne => NEGATIVE_ONE_TO_ONE,
+            ClipDepth::ZeroToOne => ZERO_TO_ONE,
+        }
+    }
+}
+
+impl From<LogicOp> for gl::types::GLenum {
+    fn from(value: LogicOp) -> Self {
+        match value {
+            LogicOp::Clear => gl::CLEAR,
+            LogicOp::Set => gl::SET,
+            LogicOp::Copy => gl::COPY,
+            LogicOp::CopyInverted => gl::COPY_INVERTED,
+            LogicOp::Noop => gl::NOOP,
+            LogicOp::Invert => gl::INVERT,
+            LogicOp::And => gl::AND,
+            LogicOp::Nand => gl::NAND,
+            LogicOp::Or => gl::OR,
+            LogicOp::Nor => gl::NOR,
+            LogicOp::Xor => gl::XOR,
+            LogicOp::Equiv => gl::EQUIV,
+            LogicOp::AndReverse => gl::AND_REVERSE,
+            LogicOp::AndInverted => gl::AND_INVERTED,
+            LogicOp::OrReverse => gl::OR_REVERSE,
+            LogicOp::OrInverted => gl::OR_INVERTED,
+        }
+    }
+}
+
+impl From<ProvokingVertex> for gl::types::GLenum {
+    fn from(value: ProvokingVertex) -> Self {
+        match value {
+            ProvokingVertex::First => gl::FIRST_VERTEX_CONVENTION,
+            ProvokingVertex::Last => gl::LAST_VERTEX_CONVENTION,
+        }
+    }
+}
+
+impl From<BarrierFlags> for gl::types::GLbitfield {
+    fn from(value: BarrierFlags) -> Self {
+        const BARRIERS: [(BarrierFlags, gl::types::GLbitfield); 13] = [
+            (
+                BarrierFlags::VERTEX_ATTRIB_ARRAY,
+                gl::VERTEX_ATTRIB_ARRAY_BARRIER_BIT,
+            ),
+            (BarrierFlags::ELEMENT_ARRAY, gl::ELEMENT_ARRAY_BARRIER_BIT),
+            (BarrierFlags::UNIFORM, gl::UNIFORM_BARRIER_BIT),
+            (BarrierFlags::TEXTURE_FETCH, gl::TEXTURE_FETCH_BARRIER_BIT),
+            (
+                BarrierFlags::SHADER_IMAGE_ACCESS,
+                gl::SHADER_IMAGE_ACCESS_BARRIER_BIT,
+            ),
+            (BarrierFlags::COMMAND, gl::COMMAND_BARRIER_BIT),
+            (BarrierFlags::PIXEL_BUFFER, gl::PIXEL_BUFFER_BARRIER_BIT),
+            (BarrierFlags::TEXTURE_UPDATE, gl::TEXTURE_UPDATE_BARRIER_BIT),
+            (BarrierFlags::BUFFER_UPDATE, gl::BUFFER_UPDATE_BARRIER_BIT),
+            (BarrierFlags::FRAMEBUFFER, gl::FRAMEBUFFER_BARRIER_BIT),
+            (
+                BarrierFlags::TRANSFORM_FEEDBACK,
+                gl::TRANSFORM_FEEDBACK_BARRIER_BIT,
+            ),
+            (BarrierFlags::ATOMIC_COUNTER, gl::ATOMIC_COUNTER_BARRIER_BIT),
+            (BarrierFlags::SHADER_STORAGE, gl::SHADER_STORAGE_BARRIER_BIT),
+        ];
+
+        BARRIERS
+            .iter()
+            .filter(|(flag, _)| value.contains(*flag))
+            .fold(0, |bits, (_, bit)| bits | bit)
+    }
+}
+
 extern "system" fn debug_callback(
     source: u32,
     kind: u32,
@@ -472,3 +1757,19 @@ extern "system" fn debug_callback(
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn spirv_test() {
+        let mut extensions = Extensions::default();
+        assert!(!extensions.spirv((4, 3)));
+        assert!(!extensions.spirv((4, 5)));
+        assert!(extensions.spirv((4, 6)));
+
+        extensions.names.insert(String::from("GL_ARB_gl_spirv"));
+        assert!(extensions.spirv((4, 3)));
+    }
+}
@@ -1,6 +1,6 @@
 use super::gl::{self, types::GLenum};
 use crate::{
-    buffer::{self, FlatData},
+    buffer::{self, FlatData, MapFlags, Mapping},
     error::Error,
 };
 
@@ -8,12 +8,16 @@ pub struct Native {
     pub(crate) id: gl::types::GLuint,
     kind: GLenum,
     usage: GLenum,
+    size: gl::types::GLsizeiptr,
+    element_type: std::any::TypeId,
+    element_size: usize,
+    pub(super) name: Option<String>,
 }
 
 struct AccessUsage(buffer::Access, buffer::Usage);
 
 impl Native {
-    pub(super) fn new<T: FlatData>(buffer: &crate::Buffer<T>) -> Result<Self, Error> {
+    pub(super) fn new<T: FlatData + 'static>(buffer: &crate::Buffer<T>) -> Result<Self, Error> {
         let mut b = Self {
             id: unsafe {
                 let mut buffer = 0;
@@ -22,6 +26,10 @@ impl Native {
             },
             kind: buffer.kind.into(),
             usage: AccessUsage(buffer.access, buffer.usage).into(),
+            size: 0,
+            element_type: std::any::TypeId::of::<T>(),
+            element_size: std::mem::size_of::<T>(),
+            name: None,
         };
 
         if let Some(data) = buffer.data {
@@ -31,7 +39,46 @@ impl Native {
         Ok(b)
     }
 
-    fn set_data<T: buffer::FlatData>(&mut self, data: &[T]) -> Result<(), Error> {
+    /// Allocates the storage for all items and writes them through a mapping, see
+    /// `Context::create_buffer_from_iter`.
+    pub(super) fn from_iter<T: FlatData + 'static>(
+        buffer: &crate::Buffer<T>,
+        items: impl ExactSizeIterator<Item = T>,
+    ) -> Result<Self, Error> {
+        let mut b = Self::new(buffer)?;
+
+        let length = items
+            .len()
+            .checked_mul(std::mem::size_of::<T>())
+            .ok_or(Error::ConversionFailed("buffer length overflows usize"))?;
+        let size = length
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("buffer length into i32"))?;
+
+        unsafe {
+            gl::BindBuffer(b.kind, b.id);
+            gl::BufferData(b.kind, size, std::ptr::null(), b.usage);
+        }
+        b.size = size;
+
+        if length == 0 {
+            return Ok(b);
+        }
+
+        let mut mapping = b.map_range(0, length, MapFlags::WRITE | MapFlags::INVALIDATE_BUFFER)?;
+        if let Some(bytes) = mapping.as_mut_slice() {
+            for (chunk, item) in bytes.chunks_exact_mut(std::mem::size_of::<T>()).zip(items) {
+                //SAFETY: the chunk is exactly one T long. The mapping has no alignment guarantee
+                //for T, so the write is unaligned.
+                unsafe { chunk.as_mut_ptr().cast::<T>().write_unaligned(item) };
+            }
+        }
+        drop(mapping);
+
+        Ok(b)
+    }
+
+    fn set_data<T: buffer::FlatData + 'static>(&mut self, data: &[T]) -> Result<(), Error> {
         let size = (data.len() * std::mem::size_of::<T>())
             .try_into()
             .map_err(|_| Error::ConversionFailed("buffer length into i32"))?;
@@ -40,22 +87,210 @@ impl Native {
             gl::BindBuffer(self.kind, self.id);
             gl::BufferData(self.kind, size, data.as_ptr().cast(), self.usage);
         }
+        self.size = size;
+        self.element_type = std::any::TypeId::of::<T>();
+        self.element_size = std::mem::size_of::<T>();
 
         Ok(())
     }
+
+    /// Maps the byte range, see `Context::map_buffer_range`.
+    //the mapping borrows the buffer mutably, so it can't be changed or deleted while mapped
+    #[allow(clippy::needless_pass_by_ref_mut)]
+    pub(super) fn map_range(
+        &mut self,
+        offset: usize,
+        length: usize,
+        flags: MapFlags,
+    ) -> Result<Mapping<'_>, Error> {
+        if !flags.intersects(MapFlags::READ | MapFlags::WRITE) {
+            return Err(Error::InvalidOperation(
+                "mapping requires read or write access",
+            ));
+        }
+
+        let write_only =
+            MapFlags::INVALIDATE_RANGE | MapFlags::INVALIDATE_BUFFER | MapFlags::UNSYNCHRONIZED;
+        if flags.contains(MapFlags::READ) && flags.intersects(write_only) {
+            return Err(Error::InvalidOperation(
+                "read access can't be combined with invalidation or unsynchronized access",
+            ));
+        }
+
+        let in_bounds = offset
+            .checked_add(length)
+            .is_some_and(|end| end <= crate::buffer::Native::size(self));
+        if !in_bounds {
+            return Err(Error::InvalidOperation(
+                "mapped range is outside of the buffer",
+            ));
+        }
+
+        let gl_offset = offset
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("mapping offset to GLintptr"))?;
+
+        let gl_length = length
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("mapping length to GLsizeiptr"))?;
+
+        let (kind, id) = (self.kind, self.id);
+        let data = unsafe {
+            gl::BindBuffer(kind, id);
+            gl::MapBufferRange(kind, gl_offset, gl_length, flags.into())
+        };
+
+        if data.is_null() {
+            return Err(Error::InvalidOperation("failed to map the buffer"));
+        }
+
+        //SAFETY: the driver returned a valid pointer to `length` bytes, which stay mapped until
+        //the mapping unmaps them on drop. The mapping borrows the buffer mutably, so it can't be
+        //deleted or mapped a second time in the meantime.
+        let data = unsafe { std::slice::from_raw_parts_mut(data.cast::<u8>(), length) };
+
+        Ok(Mapping::new(
+            data,
+            flags,
+            Box::new(move || unsafe {
+                gl::BindBuffer(kind, id);
+                gl::UnmapBuffer(kind);
+            }),
+        ))
+    }
+
+    /// Re-specifies the storage with the same size and usage, but without data.
+    pub(super) fn orphan(&mut self) {
+        unsafe {
+            gl::BindBuffer(self.kind, self.id);
+            gl::BufferData(self.kind, self.size, std::ptr::null(), self.usage);
+        }
+    }
 }
 
 impl crate::buffer::Native for Native {
-    fn set_data<T: buffer::FlatData>(&mut self, data: &[T]) -> Result<(), Error> {
+    fn set_data<T: buffer::FlatData + 'static>(&mut self, data: &[T]) -> Result<(), Error> {
         self.set_data(data)
     }
+
+    fn size(&self) -> usize {
+        //the size is only ever set from a usize
+        self.size.try_into().unwrap_or_default()
+    }
+
+    fn element_size(&self) -> usize {
+        self.element_size
+    }
+
+    fn element_type(&self) -> std::any::TypeId {
+        self.element_type
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+}
+
+/// Uniform buffer split into slots, which are written one after another, so that consecutive
+/// draws don't wait for each other to update their constants.
+pub(super) struct ConstantRing {
+    buffer: Native,
+    slot_size: usize,
+    next: usize,
+    /// Offset and size of the range bound by the last `push`
+    bound: Option<(gl::types::GLintptr, gl::types::GLsizeiptr)>,
+}
+
+impl ConstantRing {
+    const SLOTS: usize = 64;
+
+    /// Creates the ring with slots aligned to the uniform buffer offset alignment.
+    pub(super) fn new(alignment: usize) -> Result<Self, Error> {
+        let slot_size = buffer::MAX_DRAW_CONSTANTS_SIZE.next_multiple_of(alignment);
+        let storage = vec![0_u8; slot_size * Self::SLOTS];
+
+        Ok(Self {
+            buffer: Native::new(&crate::Buffer::with_uniform_data(
+                buffer::Access::Frequent,
+                buffer::Usage::Write,
+                &storage,
+            ))?,
+            slot_size,
+            next: 0,
+            bound: None,
+        })
+    }
+
+    /// Binds the range of the last `push` again, after something else was bound to
+    /// `buffer::DRAW_CONSTANTS_BINDING`.
+    pub(super) fn rebind(&self) {
+        if let Some((offset, size)) = self.bound {
+            unsafe {
+                gl::BindBufferRange(
+                    gl::UNIFORM_BUFFER,
+                    buffer::DRAW_CONSTANTS_BINDING,
+                    self.buffer.id,
+                    offset,
+                    size,
+                );
+            }
+        }
+    }
+
+    /// Writes the data into the next slot and binds it to `buffer::DRAW_CONSTANTS_BINDING`.
+    pub(super) fn push<T: FlatData>(&mut self, data: &T) -> Result<(), Error> {
+        let size = std::mem::size_of::<T>();
+        if size > buffer::MAX_DRAW_CONSTANTS_SIZE {
+            return Err(Error::InvalidOperation(
+                "draw constants are larger than MAX_DRAW_CONSTANTS_SIZE",
+            ));
+        }
+
+        //start over in fresh storage, pending draws keep the old one
+        if self.next == Self::SLOTS {
+            self.buffer.orphan();
+            self.next = 0;
+        }
+
+        let offset = self.next * self.slot_size;
+        self.next += 1;
+
+        let gl_offset = offset
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("constant offset to GLintptr"))?;
+
+        let gl_size = size
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("constant size to GLsizeiptr"))?;
+
+        unsafe {
+            gl::BindBuffer(gl::UNIFORM_BUFFER, self.buffer.id);
+            gl::BufferSubData(
+                gl::UNIFORM_BUFFER,
+                gl_offset,
+                gl_size,
+                std::ptr::from_ref(data).cast(),
+            );
+            gl::BindBufferRange(
+                gl::UNIFORM_BUFFER,
+                buffer::DRAW_CONSTANTS_BINDING,
+                self.buffer.id,
+                gl_offset,
+                gl_size,
+            );
+        }
+        self.bound = Some((gl_offset, gl_size));
+
+        Ok(())
+    }
 }
 
 impl Drop for Native {
     fn drop(&mut self) {
         log::trace!(
-            "Dropped {kind} buffer {id}.",
+            "Dropped {kind} buffer {id}{name}.",
             id = self.id,
+            name = super::display_name(self.name.as_deref()),
             kind = buffer::Kind::try_from(self.kind)
                 .map_or_else(|_| String::from("unknown"), |k| k.to_string()),
         );
@@ -68,6 +303,9 @@ impl From<buffer::Kind> for GLenum {
     fn from(value: buffer::Kind) -> Self {
         match value {
             buffer::Kind::Vertex => gl::ARRAY_BUFFER,
+            buffer::Kind::Indirect => gl::DRAW_INDIRECT_BUFFER,
+            buffer::Kind::Uniform => gl::UNIFORM_BUFFER,
+            buffer::Kind::Storage => gl::SHADER_STORAGE_BUFFER,
         }
     }
 }
@@ -77,6 +315,9 @@ impl TryFrom<GLenum> for buffer::Kind {
     fn try_from(value: GLenum) -> Result<Self, Self::Error> {
         match value {
             gl::ARRAY_BUFFER => Ok(Self::Vertex),
+            gl::DRAW_INDIRECT_BUFFER => Ok(Self::Indirect),
+            gl::UNIFORM_BUFFER => Ok(Self::Uniform),
+            gl::SHADER_STORAGE_BUFFER => Ok(Self::Storage),
             _ => Err(Error::ConversionFailed("glenum to bufferkind")),
         }
     }
@@ -105,3 +346,20 @@ impl From<AccessUsage> for GLenum {
         }
     }
 }
+
+impl From<MapFlags> for gl::types::GLbitfield {
+    fn from(value: MapFlags) -> Self {
+        const FLAGS: [(MapFlags, gl::types::GLbitfield); 5] = [
+            (MapFlags::READ, gl::MAP_READ_BIT),
+            (MapFlags::WRITE, gl::MAP_WRITE_BIT),
+            (MapFlags::INVALIDATE_RANGE, gl::MAP_INVALIDATE_RANGE_BIT),
+            (MapFlags::INVALIDATE_BUFFER, gl::MAP_INVALIDATE_BUFFER_BIT),
+            (MapFlags::UNSYNCHRONIZED, gl::MAP_UNSYNCHRONIZED_BIT),
+        ];
+
+        FLAGS
+            .iter()
+            .filter(|(flag, _)| value.contains(*flag))
+            .fold(0, |bits, (_, bit)| bits | bit)
+    }
+}
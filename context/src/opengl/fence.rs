@@ -0,0 +1,44 @@
+use super::gl;
+use crate::{error::Error, fence::Status};
+
+pub struct Native {
+    sync: gl::types::GLsync,
+}
+
+impl Native {
+    /// Inserts the fence after all commands issued so far.
+    ///
+    /// # Errors
+    /// `Error::ExternalError`: When the driver fails to create the fence.
+    pub(super) fn insert() -> Result<Self, Error> {
+        let sync = unsafe { gl::FenceSync(gl::SYNC_GPU_COMMANDS_COMPLETE, 0) };
+        if sync.is_null() {
+            return Err(Error::ExternalError(String::from(
+                "creating the fence failed",
+            )));
+        }
+
+        Ok(Self { sync })
+    }
+
+    /// Blocks until the fence is signaled or the timeout in nanoseconds runs out.
+    pub(super) fn wait(&self, timeout: u64) -> Result<Status, Error> {
+        //flushing makes sure the fence reaches the GPU, otherwise waiting could never finish
+        let status = unsafe { gl::ClientWaitSync(self.sync, gl::SYNC_FLUSH_COMMANDS_BIT, timeout) };
+
+        match status {
+            gl::ALREADY_SIGNALED | gl::CONDITION_SATISFIED => Ok(Status::Signaled),
+            gl::TIMEOUT_EXPIRED => Ok(Status::TimedOut),
+            _ => Err(Error::ExternalError(String::from(
+                "waiting for fence failed",
+            ))),
+        }
+    }
+}
+
+impl Drop for Native {
+    fn drop(&mut self) {
+        log::trace!("Dropped fence {:?}.", self.sync);
+        unsafe { gl::DeleteSync(self.sync) }
+    }
+}
@@ -0,0 +1,69 @@
+use super::gl::{self, types::GLenum};
+use crate::query;
+
+pub struct Native {
+    pub(super) id: gl::types::GLuint,
+    kind: query::Kind,
+}
+
+impl From<query::Kind> for GLenum {
+    fn from(value: query::Kind) -> Self {
+        match value {
+            query::Kind::SamplesPassed => gl::SAMPLES_PASSED,
+            query::Kind::AnySamplesPassed => gl::ANY_SAMPLES_PASSED,
+            query::Kind::PrimitivesGenerated => gl::PRIMITIVES_GENERATED,
+        }
+    }
+}
+
+impl Native {
+    /// Creates the query and starts counting.
+    pub(super) fn begin(kind: query::Kind) -> Self {
+        let query = Self {
+            id: unsafe {
+                let mut query = 0;
+                gl::GenQueries(1, &mut query);
+                query
+            },
+            kind,
+        };
+
+        unsafe {
+            gl::BeginQuery(kind.into(), query.id);
+        }
+
+        query
+    }
+
+    pub(super) fn end(&self) {
+        unsafe {
+            gl::EndQuery(self.kind.into());
+        }
+    }
+
+    /// Returns the result without waiting, or `None` if it isn't available yet.
+    pub(super) fn result(&self) -> Option<u64> {
+        let mut available = 0;
+        unsafe {
+            gl::GetQueryObjectiv(self.id, gl::QUERY_RESULT_AVAILABLE, &mut available);
+        }
+
+        if available == 0 {
+            return None;
+        }
+
+        let mut result = 0;
+        unsafe {
+            gl::GetQueryObjectui64v(self.id, gl::QUERY_RESULT, &mut result);
+        }
+
+        Some(result)
+    }
+}
+
+impl Drop for Native {
+    fn drop(&mut self) {
+        log::trace!("Dropped {} query {}.", self.kind, self.id);
+        unsafe { gl::DeleteQueries(1, &self.id) }
+    }
+}
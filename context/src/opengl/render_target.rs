@@ -2,6 +2,7 @@ use cac_core::math::URect;
 use cac_core::{image, Color32};
 
 use super::gl;
+use crate::fence::Status;
 use crate::render_target::RenderTarget;
 use crate::Error;
 
@@ -25,36 +26,188 @@ impl Native {
     }
 
     pub(super) fn bind(&mut self) -> Result<(), Error> {
-        let x = self
-            .viewport
-            .x
-            .try_into()
-            .map_err(|_| Error::ConversionFailed("viewport x conversion wraps i32"))?;
-        let y = self
-            .viewport
-            .y
-            .try_into()
-            .map_err(|_| Error::ConversionFailed("viewport y conversion wraps i32"))?;
-        let w = self
-            .viewport
-            .width
+        apply_viewport(self.viewport)
+    }
+
+    pub(super) const fn viewport(&self) -> URect {
+        self.viewport
+    }
+
+    /// Clears the color to the clear color, leaving the depth untouched.
+    pub(super) fn clear_color_only(&self) {
+        if let Some(color) = self.clear_color {
+            let [r, g, b, a] = color.as_rgba();
+            unsafe {
+                gl::ClearColor(r, g, b, a);
+                gl::Clear(gl::COLOR_BUFFER_BIT);
+            }
+        }
+    }
+}
+
+/// Sets the viewport and the scissor rect.
+///
+/// # Errors
+/// `Error::ConversionFailed`: When the viewport values wrap around `GLint`.
+pub(super) fn apply_viewport(viewport: URect) -> Result<(), Error> {
+    let (x, y, w, h) = native_rect(viewport)?;
+
+    unsafe {
+        gl::Viewport(x, y, w, h);
+        gl::Scissor(x, y, w, h);
+    }
+
+    Ok(())
+}
+
+/// Resets the depth of the bound render target to the far plane, leaving the color untouched.
+pub(super) fn clear_depth() {
+    unsafe {
+        gl::ClearDepth(1.0);
+        gl::Clear(gl::DEPTH_BUFFER_BIT);
+    }
+}
+
+/// Native format, type and size in bytes of a pixel
+const fn pixel_format(format: image::Format) -> (gl::types::GLenum, gl::types::GLenum, usize) {
+    match format {
+        image::Format::RgbU8 => (gl::RGB, gl::UNSIGNED_BYTE, 3),
+        image::Format::RgbF32 => (gl::RGB, gl::FLOAT, 12),
+        image::Format::RgbaU8 => (gl::RGBA, gl::UNSIGNED_BYTE, 4),
+        image::Format::RgbaF32 => (gl::RGBA, gl::FLOAT, 16),
+        image::Format::RedU8 => (gl::RED, gl::UNSIGNED_BYTE, 1),
+        image::Format::RedF32 => (gl::RED, gl::FLOAT, 4),
+    }
+}
+
+/// Reads pixels into a pixel pack buffer, so that the result can be retrieved later on without
+/// waiting for the GPU.
+pub struct PixelRead {
+    buffer: gl::types::GLuint,
+    fence: super::fence::Native,
+    format: image::Format,
+    rect: URect,
+    size: usize,
+}
+
+impl PixelRead {
+    /// Starts reading the pixels of the bound render target.
+    pub(super) fn new(format: image::Format, rect: URect) -> Result<Self, Error> {
+        let (gl_format, kind, pixel_size) = pixel_format(format);
+        let (x, y, width, height) = native_rect(rect)?;
+
+        let size = pixel_count(rect)?
+            .checked_mul(pixel_size)
+            .ok_or(Error::ConversionFailed("pixel buffer size overflows usize"))?;
+        let buffer_size = size
             .try_into()
-            .map_err(|_| Error::ConversionFailed("viewport width conversion wraps i32"))?;
-        let h = self
-            .viewport
-            .height
+            .map_err(|_| Error::ConversionFailed("pixel buffer size to GLsizeiptr"))?;
+
+        let mut buffer = 0;
+        unsafe {
+            gl::GenBuffers(1, &mut buffer);
+            gl::BindBuffer(gl::PIXEL_PACK_BUFFER, buffer);
+            gl::BufferData(
+                gl::PIXEL_PACK_BUFFER,
+                buffer_size,
+                std::ptr::null(),
+                gl::STREAM_READ,
+            );
+            gl::ReadPixels(x, y, width, height, gl_format, kind, std::ptr::null_mut());
+            gl::BindBuffer(gl::PIXEL_PACK_BUFFER, 0);
+        }
+
+        let fence = super::fence::Native::insert().inspect_err(|_| unsafe {
+            gl::DeleteBuffers(1, std::ptr::from_ref(&buffer));
+        })?;
+
+        Ok(Self {
+            buffer,
+            fence,
+            format,
+            rect,
+            size,
+        })
+    }
+
+    /// Returns the pixels if the GPU finished reading them, without waiting.
+    pub(super) fn try_read(&self) -> Result<Option<image::Image>, Error> {
+        if self.fence.wait(0)? == Status::TimedOut {
+            return Ok(None);
+        }
+
+        let mut data = self.format.create_storage(pixel_count(self.rect)?);
+
+        let buffer_size = self
+            .size
             .try_into()
-            .map_err(|_| Error::ConversionFailed("viewport height conversion wraps i32"))?;
+            .map_err(|_| Error::ConversionFailed("pixel buffer size to GLsizeiptr"))?;
 
         unsafe {
-            gl::Viewport(x, y, w, h);
-            gl::Scissor(x, y, w, h);
+            gl::BindBuffer(gl::PIXEL_PACK_BUFFER, self.buffer);
+            let mapped =
+                gl::MapBufferRange(gl::PIXEL_PACK_BUFFER, 0, buffer_size, gl::MAP_READ_BIT);
+
+            if !mapped.is_null() {
+                std::ptr::copy_nonoverlapping(
+                    mapped.cast::<u8>(),
+                    data.as_mut_ptr::<u8>(),
+                    self.size,
+                );
+                gl::UnmapBuffer(gl::PIXEL_PACK_BUFFER);
+            }
+            gl::BindBuffer(gl::PIXEL_PACK_BUFFER, 0);
+
+            if mapped.is_null() {
+                return Err(Error::ExternalError(String::from(
+                    "mapping the pixel buffer failed",
+                )));
+            }
         }
 
-        Ok(())
+        image::Image::new(self.rect.width, self.rect.height, self.format, data)
+            .map(Some)
+            .map_err(|e| Error::ExternalError(e.to_string()))
     }
 }
 
+impl Drop for PixelRead {
+    fn drop(&mut self) {
+        log::trace!("Dropped pixel read buffer {}.", self.buffer);
+        unsafe { gl::DeleteBuffers(1, &self.buffer) }
+    }
+}
+
+/// Number of pixels inside the rect.
+fn pixel_count(rect: URect) -> Result<usize, Error> {
+    rect.width
+        .checked_mul(rect.height)
+        .and_then(|count| usize::try_from(count).ok())
+        .ok_or(Error::ConversionFailed("rect dimensions to usize"))
+}
+
+/// Converts the rect into x, y, width and height as `GLint`.
+fn native_rect(rect: URect) -> Result<(i32, i32, i32, i32), Error> {
+    let x = rect
+        .x
+        .try_into()
+        .map_err(|_| Error::ConversionFailed("rect x conversion wraps i32"))?;
+    let y = rect
+        .y
+        .try_into()
+        .map_err(|_| Error::ConversionFailed("rect y conversion wraps i32"))?;
+    let w = rect
+        .width
+        .try_into()
+        .map_err(|_| Error::ConversionFailed("rect width conversion wraps i32"))?;
+    let h = rect
+        .height
+        .try_into()
+        .map_err(|_| Error::ConversionFailed("rect height conversion wraps i32"))?;
+
+    Ok((x, y, w, h))
+}
+
 impl crate::render_target::Native for Native {
     fn clear(&mut self) {
         let mut flags = 0;
@@ -71,6 +224,32 @@ impl crate::render_target::Native for Native {
         }
     }
 
+    fn clear_rect(&mut self, rect: URect) -> Result<(), Error> {
+        let (x, y, w, h) = native_rect(rect)?;
+
+        //the scissor might belong to a pushed viewport instead of the one of the render target,
+        //and the test might be disabled with `Context::set_scissor_test`
+        let mut scissor = [0; 4];
+        let scissor_test = unsafe {
+            gl::GetIntegerv(gl::SCISSOR_BOX, scissor.as_mut_ptr());
+            gl::IsEnabled(gl::SCISSOR_TEST)
+        } == gl::TRUE;
+        unsafe {
+            gl::Enable(gl::SCISSOR_TEST);
+            gl::Scissor(x, y, w, h);
+        }
+        self.clear();
+        unsafe {
+            let [scissor_x, scissor_y, scissor_w, scissor_h] = scissor;
+            gl::Scissor(scissor_x, scissor_y, scissor_w, scissor_h);
+            if !scissor_test {
+                gl::Disable(gl::SCISSOR_TEST);
+            }
+        }
+
+        Ok(())
+    }
+
     fn set_clear_color(&mut self, color: Option<Color32>) {
         self.clear_color = color;
     }
@@ -82,32 +261,8 @@ impl crate::render_target::Native for Native {
 
         let mut data = format.create_storage(count);
 
-        let (gl_format, kind) = match format {
-            image::Format::RgbU8 => (gl::RGB, gl::UNSIGNED_BYTE),
-            image::Format::RgbF32 => (gl::RGB, gl::FLOAT),
-            image::Format::RgbaU8 => (gl::RGBA, gl::UNSIGNED_BYTE),
-            image::Format::RgbaF32 => (gl::RGBA, gl::FLOAT),
-        };
-
-        let x = rect
-            .x
-            .try_into()
-            .map_err(|_| Error::ConversionFailed("rect.x to GLint"))?;
-
-        let y = rect
-            .y
-            .try_into()
-            .map_err(|_| Error::ConversionFailed("rect.y to GLint"))?;
-
-        let width = rect
-            .width
-            .try_into()
-            .map_err(|_| Error::ConversionFailed("rect.width to GLsizei"))?;
-
-        let height = rect
-            .height
-            .try_into()
-            .map_err(|_| Error::ConversionFailed("rect.height to GLsizei"))?;
+        let (gl_format, kind, _) = pixel_format(format);
+        let (x, y, width, height) = native_rect(rect)?;
 
         unsafe { gl::ReadPixels(x, y, width, height, gl_format, kind, data.as_mut_ptr()) };
 
@@ -1,14 +1,34 @@
-use cac_core::gen_vec::GenVec;
+use std::ffi::CString;
 
-use crate::{error::Error, shader::Shader};
+use cac_core::{gen_vec::GenVec, math::Mat4};
+
+use crate::{
+    error::Error,
+    shader::{self, AttributeInfo, Shader, ShaderReflection, UniformInfo},
+};
 
 use super::{
-    gl::{self, types::GLuint},
+    gl::{
+        self,
+        types::{GLchar, GLenum, GLint, GLsizei, GLuint},
+    },
     stage,
 };
 
+type GetActiveVariable =
+    unsafe fn(GLuint, GLuint, GLsizei, *mut GLsizei, *mut GLint, *mut GLenum, *mut GLchar);
+type GetVariableLocation = unsafe fn(GLuint, *const GLchar) -> GLint;
+
+/// Name, location, type and size of an active variable
+type Variable = (String, Option<u32>, u32, usize);
+
 pub struct Native {
     pub(super) id: GLuint,
+    pub(super) name: Option<String>,
+    /// Location of `shader::TRANSFORM_UNIFORM`, if the program uses it
+    transform_location: Option<GLint>,
+    /// Unknown for programs created from binaries, which are assumed not to declare it
+    early_fragment_tests: bool,
 }
 
 impl Native {
@@ -16,8 +36,11 @@ impl Native {
         shader: Shader,
         stages: &GenVec<crate::handle::Stage, super::stage::Native>,
     ) -> Result<Self, Error> {
-        let program = Self {
+        let mut program = Self {
             id: unsafe { gl::CreateProgram() },
+            name: None,
+            transform_location: None,
+            early_fragment_tests: false,
         };
         let temp_stages = shader
             .stage_sources
@@ -40,6 +63,11 @@ impl Native {
             });
 
         program.link()?;
+        program.transform_location = program.uniform_location(shader::TRANSFORM_UNIFORM);
+        program.early_fragment_tests = temp_stages
+            .iter()
+            .chain(stages.iter().copied())
+            .any(|stage| stage.early_fragment_tests);
 
         temp_stages
             .iter()
@@ -51,14 +79,218 @@ impl Native {
         Ok(program)
     }
 
+    pub(super) const fn early_fragment_tests(&self) -> bool {
+        self.early_fragment_tests
+    }
+
     pub(super) fn bind(&mut self) {
         unsafe { gl::UseProgram(self.id) }
     }
 
+    fn uniform_location(&self, name: &str) -> Option<GLint> {
+        let name = CString::new(name).ok()?;
+        let location = unsafe { gl::GetUniformLocation(self.id, name.as_ptr()) };
+
+        (location >= 0).then_some(location)
+    }
+
+    /// Assigns the uniform block to the binding point, see `Context::set_uniform_block_binding`.
+    pub(super) fn set_uniform_block_binding(
+        &self,
+        block_name: &str,
+        binding: u32,
+    ) -> Result<(), Error> {
+        let name = CString::new(block_name)
+            .map_err(|_| Error::ConversionFailed("block name to CString"))?;
+        let index = unsafe { gl::GetUniformBlockIndex(self.id, name.as_ptr()) };
+        if index == gl::INVALID_INDEX {
+            return Err(Error::InvalidOperation(
+                "uniform block isn't active in the shader",
+            ));
+        }
+
+        unsafe { gl::UniformBlockBinding(self.id, index, binding) };
+        Ok(())
+    }
+
+    /// Sets the transform uniform, if the program uses it.
+    pub(super) fn set_transform(&self, transform: &Mat4) {
+        if let Some(location) = self.transform_location {
+            let columns = transform.to_cols_array();
+            unsafe {
+                gl::ProgramUniformMatrix4fv(self.id, location, 1, gl::FALSE, columns.as_ptr());
+            }
+        }
+    }
+
+    pub(super) fn reflect(&self) -> Result<ShaderReflection, Error> {
+        let attributes = self
+            .active_variables(
+                gl::ACTIVE_ATTRIBUTES,
+                gl::ACTIVE_ATTRIBUTE_MAX_LENGTH,
+                gl::GetActiveAttrib,
+                gl::GetAttribLocation,
+            )?
+            .into_iter()
+            .map(|(name, location, kind, size)| AttributeInfo {
+                name,
+                location,
+                kind,
+                size,
+            })
+            .collect();
+
+        let uniforms = self
+            .active_variables(
+                gl::ACTIVE_UNIFORMS,
+                gl::ACTIVE_UNIFORM_MAX_LENGTH,
+                gl::GetActiveUniform,
+                gl::GetUniformLocation,
+            )?
+            .into_iter()
+            .map(|(name, location, kind, size)| UniformInfo {
+                name,
+                location,
+                kind,
+                size,
+            })
+            .collect();
+
+        Ok(ShaderReflection {
+            attributes,
+            uniforms,
+        })
+    }
+
+    fn active_variables(
+        &self,
+        count: GLenum,
+        max_length: GLenum,
+        get_active: GetActiveVariable,
+        get_location: GetVariableLocation,
+    ) -> Result<Vec<Variable>, Error> {
+        let (mut variable_count, mut name_length) = (0, 0);
+        unsafe {
+            gl::GetProgramiv(self.id, count, &mut variable_count);
+            gl::GetProgramiv(self.id, max_length, &mut name_length);
+        }
+
+        let variable_count: GLuint = variable_count
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("active variable count to GLuint"))?;
+
+        let mut name = vec![0_u8; usize::try_from(name_length).unwrap_or_default() + 1];
+        let buffer_size = name
+            .len()
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("variable name length to GLsizei"))?;
+
+        (0..variable_count)
+            .map(|index| {
+                let (mut length, mut size, mut kind) = (0, 0, 0);
+                unsafe {
+                    get_active(
+                        self.id,
+                        index,
+                        buffer_size,
+                        &mut length,
+                        &mut size,
+                        &mut kind,
+                        name.as_mut_ptr().cast(),
+                    );
+                }
+
+                let length = usize::try_from(length)
+                    .map_err(|_| Error::ConversionFailed("variable name length to usize"))?;
+
+                //the name is null terminated, which is required to query the location
+                let location = unsafe { get_location(self.id, name.as_ptr().cast()) };
+                let name = String::from_utf8_lossy(&name[..length]).to_string();
+
+                let size = size
+                    .try_into()
+                    .map_err(|_| Error::ConversionFailed("variable size to usize"))?;
+
+                Ok((name, location.try_into().ok(), kind, size))
+            })
+            .collect()
+    }
+
+    /// Creates the program from a binary retrieved with `binary`.
+    pub(super) fn from_binary(format: u32, binary: &[u8]) -> Result<Self, Error> {
+        let length = binary
+            .len()
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("program binary length to GLsizei"))?;
+
+        let mut program = Self {
+            id: unsafe { gl::CreateProgram() },
+            name: None,
+            transform_location: None,
+            early_fragment_tests: false,
+        };
+
+        unsafe {
+            gl::ProgramBinary(program.id, format, binary.as_ptr().cast(), length);
+        }
+
+        //drivers reject binaries from other versions or hardware with a link error
+        program.link_status().map_err(|e| match e {
+            Error::FailedToLinkShader(reason) => {
+                Error::FailedToLinkShader(format!("program binary was rejected: {reason}"))
+            }
+            e => e,
+        })?;
+        program.transform_location = program.uniform_location(shader::TRANSFORM_UNIFORM);
+
+        Ok(program)
+    }
+
+    /// Retrieves the binary format and the binary of the linked program.
+    pub(super) fn binary(&self) -> Result<(u32, Vec<u8>), Error> {
+        let mut length = 0;
+        unsafe {
+            gl::GetProgramiv(self.id, gl::PROGRAM_BINARY_LENGTH, &mut length);
+        }
+
+        if length == 0 {
+            return Err(Error::InvalidContext(String::from(
+                "the driver doesn't support program binaries",
+            )));
+        }
+
+        let mut binary = vec![0_u8; usize::try_from(length).unwrap_or_default()];
+        let (mut written, mut format) = (0, 0);
+        unsafe {
+            gl::GetProgramBinary(
+                self.id,
+                length,
+                &mut written,
+                &mut format,
+                binary.as_mut_ptr().cast(),
+            );
+        }
+
+        binary.truncate(usize::try_from(written).unwrap_or_default());
+        Ok((format, binary))
+    }
+
     fn link(&self) -> Result<(), Error> {
-        let mut link_status = 0;
         unsafe {
+            gl::ProgramParameteri(
+                self.id,
+                gl::PROGRAM_BINARY_RETRIEVABLE_HINT,
+                gl::TRUE.into(),
+            );
             gl::LinkProgram(self.id);
+        }
+
+        self.link_status()
+    }
+
+    fn link_status(&self) -> Result<(), Error> {
+        let mut link_status = 0;
+        unsafe {
             gl::GetProgramiv(self.id, gl::LINK_STATUS, &mut link_status);
         }
 
@@ -95,11 +327,19 @@ impl Native {
     }
 }
 
-impl crate::shader::Native for Native {}
+impl crate::shader::Native for Native {
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+}
 
 impl Drop for Native {
     fn drop(&mut self) {
-        log::trace!("Dropped shader program {}.", self.id);
+        log::trace!(
+            "Dropped shader program {}{}.",
+            self.id,
+            super::display_name(self.name.as_deref())
+        );
         unsafe { gl::DeleteProgram(self.id) }
     }
 }
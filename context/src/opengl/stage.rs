@@ -12,15 +12,19 @@ use crate::{
 pub struct Native {
     pub(crate) id: GLuint,
     kind: shader::Kind,
+    pub(super) name: Option<String>,
+    /// Fragment stage declaring `layout(early_fragment_tests) in;`
+    pub(super) early_fragment_tests: bool,
 }
 
 impl From<shader::Kind> for GLenum {
     fn from(value: shader::Kind) -> Self {
-        use gl::{FRAGMENT_SHADER, VERTEX_SHADER};
+        use gl::{COMPUTE_SHADER, FRAGMENT_SHADER, VERTEX_SHADER};
 
         match value {
             shader::Kind::Vertex => VERTEX_SHADER,
             shader::Kind::Fragment => FRAGMENT_SHADER,
+            shader::Kind::Compute => COMPUTE_SHADER,
         }
     }
 }
@@ -30,6 +34,9 @@ impl Native {
         let shader = Self {
             id: unsafe { gl::CreateShader(stage.kind.into()) },
             kind: stage.kind,
+            name: None,
+            early_fragment_tests: matches!(stage.kind, shader::Kind::Fragment)
+                && shader::declares_early_fragment_tests(stage.sources),
         };
 
         shader.compile(stage.sources)?;
@@ -102,7 +109,12 @@ impl Native {
 
 impl Drop for Native {
     fn drop(&mut self) {
-        log::trace!("Dropped {} shader {}.", self.kind, self.id);
+        log::trace!(
+            "Dropped {} shader {}{}.",
+            self.kind,
+            self.id,
+            super::display_name(self.name.as_deref())
+        );
         unsafe { gl::DeleteShader(self.id) }
     }
 }
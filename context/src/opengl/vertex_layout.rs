@@ -4,6 +4,7 @@ use crate::{
     error::Error,
     handle,
     vertex_layout::{self, BufferAttributes},
+    BufferHandle,
 };
 
 use super::gl::{
@@ -13,6 +14,8 @@ use super::gl::{
 
 pub struct Native {
     pub(super) id: GLuint,
+    pub(super) buffers: Vec<BufferHandle>,
+    pub(super) name: Option<String>,
 }
 
 impl From<vertex_layout::AttributeKind> for GLenum {
@@ -37,6 +40,8 @@ impl Native {
                 gl::GenVertexArrays(1, &mut vao);
                 vao
             },
+            buffers: layout.attributes.iter().filter_map(|a| a.buffer).collect(),
+            name: None,
         };
 
         vao.bind();
@@ -46,6 +51,20 @@ impl Native {
         Ok(vao)
     }
 
+    /// A vertex array without any attributes, for draws that generate their vertices in the
+    /// shader. The core profile requires a vertex array to be bound, even when it's unused.
+    pub(super) fn empty() -> Self {
+        Self {
+            id: unsafe {
+                let mut vao = 0;
+                gl::GenVertexArrays(1, &mut vao);
+                vao
+            },
+            buffers: Vec::new(),
+            name: None,
+        }
+    }
+
     pub(super) fn bind(&mut self) {
         unsafe {
             gl::BindVertexArray(self.id);
@@ -91,6 +110,16 @@ impl Native {
             if let Some(buffer) = buffer_attribute.buffer {
                 let vbo = buffers.get(buffer).ok_or(Error::ResourceNotFound)?;
 
+                let element_size = crate::buffer::Native::element_size(vbo);
+                if element_size != 0 && !buffer_attribute.stride().is_multiple_of(element_size) {
+                    log::warn!(
+                        "stride of attribute set {location} ({stride} bytes) doesn't match the \
+                        elements of buffer {id} ({element_size} bytes)",
+                        stride = buffer_attribute.stride(),
+                        id = vbo.id,
+                    );
+                }
+
                 let location = location
                     .try_into()
                     .map_err(|_| Error::ConversionFailed("buffer location wraps"))?;
@@ -114,11 +143,19 @@ impl Native {
     }
 }
 
-impl crate::vertex_layout::Native for Native {}
+impl crate::vertex_layout::Native for Native {
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+}
 
 impl Drop for Native {
     fn drop(&mut self) {
-        log::trace!("Dropped vertex layout {}.", self.id);
+        log::trace!(
+            "Dropped vertex layout {}{}.",
+            self.id,
+            super::display_name(self.name.as_deref())
+        );
         unsafe { gl::DeleteVertexArrays(1, &self.id) }
     }
 }
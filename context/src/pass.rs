@@ -0,0 +1,74 @@
+use cac_core::math::URect;
+
+use crate::{mesh::Mesh, RenderTargetHandle, ShaderHandle};
+
+/// State shared by all draws of a pass, applied once by `Context::run_pass` instead of before
+/// every draw.
+///
+/// Blending and culling aren't part of the pass, the context doesn't expose them yet.
+#[derive(Copy, Clone, PartialEq)]
+pub struct RenderPass {
+    pub target: RenderTargetHandle,
+    /// Clears the target with its clear color before the first draw
+    pub clear: bool,
+    /// Viewport of the draws, the previous one is restored after the pass
+    pub viewport: Option<URect>,
+    /// Near and far of the window depth range, see `Context::set_depth_range`.
+    /// The previous depth range is restored after the pass.
+    pub depth_range: Option<(f32, f32)>,
+    /// Whether the draws are depth tested, see `Context::set_depth_test`.
+    /// The previous setting is restored after the pass.
+    pub depth_test: Option<bool>,
+}
+
+impl RenderPass {
+    /// Creates a pass drawing onto the target without changing any state.
+    #[must_use]
+    pub const fn new(target: RenderTargetHandle) -> Self {
+        Self {
+            target,
+            clear: false,
+            viewport: None,
+            depth_range: None,
+            depth_test: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_clear(mut self) -> Self {
+        self.clear = true;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_viewport(mut self, viewport: URect) -> Self {
+        self.viewport = Some(viewport);
+        self
+    }
+
+    #[must_use]
+    pub const fn with_depth_range(mut self, near: f32, far: f32) -> Self {
+        self.depth_range = Some((near, far));
+        self
+    }
+
+    #[must_use]
+    pub const fn with_depth_test(mut self, enabled: bool) -> Self {
+        self.depth_test = Some(enabled);
+        self
+    }
+}
+
+/// A single draw inside of a `RenderPass`
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct DrawCommand {
+    pub shader: ShaderHandle,
+    pub mesh: Mesh,
+}
+
+impl DrawCommand {
+    #[must_use]
+    pub const fn new(shader: ShaderHandle, mesh: Mesh) -> Self {
+        Self { shader, mesh }
+    }
+}
@@ -0,0 +1,22 @@
+use std::fmt::Display;
+
+/// What a query counts between `Context::begin_query` and `Context::end_query`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// Number of samples that passed the depth and stencil tests, for occlusion queries
+    SamplesPassed,
+    /// 1 if any sample passed the depth and stencil tests, 0 otherwise
+    AnySamplesPassed,
+    /// Number of primitives emitted by the vertex processing stages
+    PrimitivesGenerated,
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SamplesPassed => write!(f, "samples passed"),
+            Self::AnySamplesPassed => write!(f, "any samples passed"),
+            Self::PrimitivesGenerated => write!(f, "primitives generated"),
+        }
+    }
+}
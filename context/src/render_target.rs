@@ -25,6 +25,13 @@ pub trait Native {
     /// Depends on the native implementation
     fn read_pixels(&self, format: image::Format, viewport: URect) -> Result<image::Image, Error>;
     fn clear(&mut self);
+
+    /// Clears only the pixels inside the rect, for example to redraw a dirty region.
+    ///
+    /// # Errors
+    /// `Error::ConversionError`: When the rect can't be converted into the native values
+    /// without wrapping or overflowing.
+    fn clear_rect(&mut self, rect: URect) -> Result<(), Error>;
     fn set_clear_color(&mut self, color: Option<Color32>);
     fn set_viewport(&mut self, viewport: URect);
 }
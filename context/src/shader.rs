@@ -4,6 +4,7 @@ use std::fmt::Display;
 pub enum Kind {
     Vertex,
     Fragment,
+    Compute,
 }
 
 #[derive(Copy, Clone)]
@@ -27,9 +28,117 @@ impl<'a> Stage<'a> {
             sources,
         }
     }
+    #[must_use]
+    pub const fn new_compute(sources: &'a [&'a str]) -> Self {
+        Self {
+            kind: Kind::Compute,
+            sources,
+        }
+    }
 }
 
-pub trait Native {}
+pub trait Native {
+    /// Name for diagnostics, set with `Context::set_shader_name`
+    fn name(&self) -> Option<&str>;
+}
+
+/// Active vertex attribute of a linked shader
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AttributeInfo {
+    pub name: String,
+    /// `None` for built-in attributes, like `gl_VertexID`
+    pub location: Option<u32>,
+    /// Type of the attribute as identified by the native graphics API
+    pub kind: u32,
+    /// Number of elements, greater than one for arrays
+    pub size: usize,
+}
+
+/// Whether the sources declare `layout(early_fragment_tests) in;`, also as part of a combined
+/// qualifier like `layout(early_fragment_tests, ...) in;`, ignoring whitespace and comments.
+///
+/// Only sources can be inspected, the declaration isn't part of the queryable state of a shader
+/// created from a binary.
+pub(crate) fn declares_early_fragment_tests(sources: &[&str]) -> bool {
+    sources.iter().any(|source| {
+        let code: String = strip_comments(source)
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+
+        code.match_indices("layout(").any(|(start, layout)| {
+            let qualifiers = &code[start + layout.len()..];
+            qualifiers
+                .split_once(')')
+                .is_some_and(|(qualifiers, rest)| {
+                    rest.starts_with("in;")
+                        && qualifiers.split(',').any(|q| q == "early_fragment_tests")
+                })
+        })
+    })
+}
+
+/// Replaces the line and block comments of the GLSL source with a space.
+fn strip_comments(source: &str) -> String {
+    let mut code = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('/', Some('/')) => {
+                chars.by_ref().find(|c| *c == '\n');
+                code.push(' ');
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                chars.by_ref().find(|c| {
+                    let end = previous == '*' && *c == '/';
+                    previous = *c;
+                    end
+                });
+                code.push(' ');
+            }
+            _ => code.push(c),
+        }
+    }
+
+    code
+}
+
+/// Name of the mat4 uniform set by `Context::draw_transformed`
+pub const TRANSFORM_UNIFORM: &str = "u_transform";
+
+/// Active uniform of a linked shader
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UniformInfo {
+    pub name: String,
+    /// `None` for uniforms inside of uniform blocks
+    pub location: Option<u32>,
+    /// Type of the uniform as identified by the native graphics API
+    pub kind: u32,
+    /// Number of elements, greater than one for arrays
+    pub size: usize,
+}
+
+/// Summary of the active attributes and uniforms of a linked shader
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ShaderReflection {
+    pub attributes: Vec<AttributeInfo>,
+    pub uniforms: Vec<UniformInfo>,
+}
+
+impl ShaderReflection {
+    #[must_use]
+    pub fn attribute(&self, name: &str) -> Option<&AttributeInfo> {
+        self.attributes.iter().find(|a| a.name == name)
+    }
+
+    #[must_use]
+    pub fn uniform(&self, name: &str) -> Option<&UniformInfo> {
+        self.uniforms.iter().find(|u| u.name == name)
+    }
+}
 
 #[derive(Clone, Copy)]
 pub struct Shader<'a> {
@@ -60,6 +169,61 @@ impl Display for Kind {
         match self {
             Self::Vertex => write!(f, "vertex"),
             Self::Fragment => write!(f, "fragment"),
+            Self::Compute => write!(f, "compute"),
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn early_fragment_tests_test() {
+        assert!(declares_early_fragment_tests(&[
+            "#version 430 core\nlayout(early_fragment_tests) in;"
+        ]));
+        assert!(declares_early_fragment_tests(&[
+            "layout ( early_fragment_tests )\n\tin ;"
+        ]));
+        assert!(!declares_early_fragment_tests(&["#version 430 core"]));
+        assert!(!declares_early_fragment_tests(&[
+            "layout(location = 0) out vec4 color;"
+        ]));
+    }
+
+    #[test]
+    fn early_fragment_tests_comments_test() {
+        assert!(!declares_early_fragment_tests(&[
+            "// layout(early_fragment_tests) in;"
+        ]));
+        assert!(!declares_early_fragment_tests(&[
+            "/* layout(early_fragment_tests) in; */"
+        ]));
+        assert!(!declares_early_fragment_tests(&[
+            "/*\nlayout(early_fragment_tests) in;\n*/"
+        ]));
+        assert!(declares_early_fragment_tests(&[
+            "/* early */ layout(/* tests */ early_fragment_tests) in; // enabled"
+        ]));
+        assert!(declares_early_fragment_tests(&[
+            "/*/ still a comment */ layout(early_fragment_tests) in;"
+        ]));
+    }
+
+    #[test]
+    fn early_fragment_tests_combined_test() {
+        assert!(declares_early_fragment_tests(&[
+            "layout(early_fragment_tests, post_depth_coverage) in;"
+        ]));
+        assert!(declares_early_fragment_tests(&[
+            "layout(post_depth_coverage,early_fragment_tests) in;"
+        ]));
+        assert!(!declares_early_fragment_tests(&[
+            "layout(early_fragment_tests) out vec4 color;"
+        ]));
+        assert!(!declares_early_fragment_tests(&[
+            "layout(not_early_fragment_tests) in;"
+        ]));
+    }
+}
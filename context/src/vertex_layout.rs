@@ -1,7 +1,12 @@
 use crate::error::Error;
 
-pub trait Native {}
+pub trait Native {
+    /// Name for diagnostics, set with `Context::set_layout_name`
+    fn name(&self) -> Option<&str>;
+}
 
+/// Compared and hashed by the attributes, stride and offset, so layouts with the same structure
+/// are equal regardless of the buffers they read from.
 #[derive(Clone, Default)]
 pub struct BufferAttributes {
     pub attributes: Vec<VertexAttribute>,
@@ -24,7 +29,27 @@ impl BufferAttributes {
     }
 }
 
-#[derive(Clone)]
+impl PartialEq for BufferAttributes {
+    fn eq(&self, other: &Self) -> bool {
+        self.attributes == other.attributes
+            && self.stride == other.stride
+            && self.offset == other.offset
+    }
+}
+
+impl Eq for BufferAttributes {}
+
+impl std::hash::Hash for BufferAttributes {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.attributes.hash(state);
+        self.stride.hash(state);
+        self.offset.hash(state);
+    }
+}
+
+/// Equal layouts have the same vertex structure, which makes them usable as a cache key for
+/// pipeline state, the buffers are ignored.
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct VertexLayout {
     pub attributes: Vec<BufferAttributes>,
 }
@@ -44,6 +69,22 @@ impl VertexLayout {
         });
     }
 
+    /// Returns every attribute location used by the layout, across all attribute sets.
+    ///
+    /// The locations are sorted and each location is only listed once.
+    #[must_use]
+    pub fn locations(&self) -> Vec<u8> {
+        let mut locations: Vec<u8> = self
+            .attributes
+            .iter()
+            .flat_map(|a| a.attributes.iter().map(|a| a.location))
+            .collect();
+
+        locations.sort_unstable();
+        locations.dedup();
+        locations
+    }
+
     /// Sets a buffer for a specific attribute set, refered to via the index of that set.
     ///
     /// # Errors
@@ -67,7 +108,7 @@ impl VertexLayout {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
 pub struct VertexAttribute {
     pub location: u8,
     pub components: Components,
@@ -89,14 +130,14 @@ impl VertexAttribute {
     }
 }
 
-#[derive(Copy, Default, Clone, PartialEq, Eq)]
+#[derive(Copy, Default, Clone, PartialEq, Eq, Hash)]
 pub enum Stride {
     #[default]
     Interleaved,
     Bytes(usize),
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Components {
     Scalar,
     Vec2,
@@ -116,7 +157,7 @@ impl Components {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
 pub enum AttributeKind {
     F32,
     U8,
@@ -131,3 +172,47 @@ impl AttributeKind {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn locations_test() {
+        let mut layout = VertexLayout::new();
+        layout.push_attributes([
+            VertexAttribute::with_f32(2, Components::Vec2, 0),
+            VertexAttribute::with_f32(0, Components::Vec3, 8),
+        ]);
+        layout.push_attributes([VertexAttribute::with_f32(1, Components::Vec4, 0)]);
+
+        assert_eq!(layout.locations(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn equality_ignores_buffers_test() {
+        use std::hash::{BuildHasher, RandomState};
+
+        let mut buffers = cac_core::gen_vec::GenVec::new();
+        let first: crate::BufferHandle = buffers.insert(());
+        let second = buffers.insert(());
+
+        let attributes = [
+            VertexAttribute::with_f32(0, Components::Vec3, 0),
+            VertexAttribute::with_f32(1, Components::Vec2, 12),
+        ];
+        let mut layout = VertexLayout::new();
+        layout.push_attributes(attributes);
+        let mut other = layout.clone();
+
+        layout.set_buffer(0, first, Stride::Interleaved, 0).unwrap();
+        other.set_buffer(0, second, Stride::Interleaved, 0).unwrap();
+
+        let hasher = RandomState::new();
+        assert!(layout == other);
+        assert_eq!(hasher.hash_one(&layout), hasher.hash_one(&other));
+
+        other.set_buffer(0, second, Stride::Bytes(32), 0).unwrap();
+        assert!(layout != other);
+    }
+}
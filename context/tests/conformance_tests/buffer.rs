@@ -1,5 +1,266 @@
-use crate::TestCase;
+use cac_context::{
+    buffer::{Access, FlatData, Kind, MapFlags, Native, Usage},
+    vertex_layout::{Components, Stride, VertexAttribute},
+    Buffer, Context, Error, Primitive,
+};
 
-pub const fn tests() -> Vec<TestCase> {
-    Vec::new()
+use crate::{
+    draw::{
+        black_screen, count_pixels, create_shader, create_vec3_layout, screenshot, POINT_VS, WHITE,
+        WHITE_FS,
+    },
+    TestCase, TestResult,
+};
+
+pub fn tests() -> Vec<TestCase> {
+    vec![
+        TEST!(orphan),
+        TEST!(element_size),
+        TEST!(name),
+        TEST!(map_buffer_range),
+        TEST!(bind_buffer_range),
+        TEST!(create_buffer_from_iter),
+    ]
+}
+
+fn orphan(ctx: &mut impl Context) -> TestResult {
+    let left: [f32; 3] = [-0.5, 0.0, 2.0];
+    let right: [f32; 3] = [0.5, 0.0, 2.0];
+
+    let buffer = ctx.create_buffer(&Buffer::with_vertex_data(
+        Access::Frequent,
+        Usage::Write,
+        &left,
+    ))?;
+    let layout = ctx.create_simple_layout(
+        buffer,
+        &[VertexAttribute::with_f32(0, Components::Vec3, 0)],
+        Stride::Interleaved,
+    )?;
+    let shader = create_shader(ctx, POINT_VS, WHITE_FS)?;
+    ctx.set_program_point_size(true);
+
+    let target = black_screen(ctx)?;
+    ctx.draw(target, Primitive::Points, shader, layout, 0, 1)?;
+
+    ctx.orphan_buffer(buffer)?;
+    check!(ctx.buffer(buffer).map(Native::size) == Some(std::mem::size_of_val(&left)));
+
+    if let Some(b) = ctx.buffer_mut(buffer) {
+        b.set_data(&right)?;
+    }
+    ctx.draw(target, Primitive::Points, shader, layout, 0, 1)?;
+
+    let errors = ctx.poll_errors();
+    check!(errors.is_none());
+
+    //both draws are visible, the first one wasn't affected by the orphaning
+    let img = screenshot(ctx, target)?;
+    let half = img.width / 2;
+    check!(count_pixels(&img, WHITE, 0, half) == 4);
+    check!(count_pixels(&img, WHITE, half, img.width) == 4);
+
+    Ok(())
+}
+
+fn element_size(ctx: &mut impl Context) -> TestResult {
+    #[repr(C)]
+    struct Vertex {
+        position: [f32; 3],
+        color: [f32; 4],
+    }
+    unsafe impl FlatData for Vertex {}
+
+    let vertices = [Vertex {
+        position: [0.0; 3],
+        color: [1.0; 4],
+    }];
+
+    let vertex_buffer = ctx.create_buffer(&Buffer::with_vertex_data(
+        Access::Once,
+        Usage::Write,
+        &vertices,
+    ))?;
+    let float_buffer = ctx.create_buffer(&Buffer::with_vertex_data(
+        Access::Once,
+        Usage::Write,
+        &[0.0_f32; 7],
+    ))?;
+
+    check!(ctx.buffer_element_size(vertex_buffer) == Some(28));
+    check!(ctx.buffer_element_size(float_buffer) == Some(4));
+    check!(
+        ctx.buffer(vertex_buffer).map(Native::element_type)
+            == Some(std::any::TypeId::of::<Vertex>())
+    );
+
+    //uploading a different type replaces the element type and size
+    if let Some(b) = ctx.buffer_mut(vertex_buffer) {
+        b.set_data(&[0_u16; 14])?;
+    }
+    check!(ctx.buffer_element_size(vertex_buffer) == Some(2));
+    check!(
+        ctx.buffer(vertex_buffer).map(Native::element_type) == Some(std::any::TypeId::of::<u16>())
+    );
+
+    Ok(())
+}
+
+fn name(ctx: &mut impl Context) -> TestResult {
+    let data: [f32; 3] = [0.0, 0.0, 1.0];
+    let buffer = ctx.create_buffer(&Buffer::with_vertex_data(Access::Once, Usage::Write, &data))?;
+
+    let unnamed = ctx.buffer(buffer).and_then(Native::name).map(String::from);
+    check!(unnamed.is_none());
+
+    ctx.set_buffer_name(buffer, "terrain_vbo")?;
+    let named = ctx.buffer(buffer).and_then(Native::name).map(String::from);
+    check!(named.as_deref() == Some("terrain_vbo"));
+
+    //the name shows up in the trace log of the drop
+    ctx.delete_buffer(buffer)?;
+    let renamed = ctx.set_buffer_name(buffer, "deleted");
+    check!(matches!(renamed, Err(Error::ResourceNotFound)));
+
+    Ok(())
+}
+
+fn map_buffer_range(ctx: &mut impl Context) -> TestResult {
+    let data: [u8; 8] = [0, 1, 2, 3, 4, 5, 6, 7];
+    let buffer = ctx.create_buffer(&Buffer::with_vertex_data(
+        Access::Frequent,
+        Usage::Write,
+        &data,
+    ))?;
+
+    {
+        let mut mapping =
+            ctx.map_buffer_range(buffer, 2, 4, MapFlags::WRITE | MapFlags::INVALIDATE_RANGE)?;
+        check!(mapping.as_slice().is_none());
+
+        let bytes = mapping
+            .as_mut_slice()
+            .ok_or_else(|| anyhow::anyhow!("write mapping without write access"))?;
+        bytes.copy_from_slice(&[10, 11, 12, 13]);
+    }
+
+    let mapping = ctx.map_buffer_range(buffer, 0, 8, MapFlags::READ)?;
+    let read = mapping.as_slice().map(<[u8]>::to_vec);
+    check!(read == Some(vec![0, 1, 10, 11, 12, 13, 6, 7]));
+    drop(mapping);
+
+    let out_of_bounds = matches!(
+        ctx.map_buffer_range(buffer, 4, 8, MapFlags::READ),
+        Err(Error::InvalidOperation(_))
+    );
+    check!(out_of_bounds);
+
+    let read_invalidate = matches!(
+        ctx.map_buffer_range(buffer, 0, 8, MapFlags::READ | MapFlags::INVALIDATE_RANGE),
+        Err(Error::InvalidOperation(_))
+    );
+    check!(read_invalidate);
+
+    Ok(())
+}
+
+fn bind_buffer_range(ctx: &mut impl Context) -> TestResult {
+    const OFFSETS_VS: &str = r"
+    #version 430 core
+    layout(location = 0) in vec3 pos;
+    layout(std140, binding = 1) uniform First {
+        vec4 first;
+    };
+    layout(std140, binding = 2) uniform Second {
+        vec4 second;
+    };
+
+    void main() {
+        vec4 offset = gl_VertexID == 0 ? first : second;
+        gl_Position = vec4(pos.xy + offset.xy, 0.0, 1.0);
+        gl_PointSize = pos.z;
+    }
+    ";
+
+    //256 bytes is the largest offset alignment allowed
+    let mut offsets = [0.0_f32; 128];
+    offsets[0] = -0.5;
+    offsets[64] = 0.5;
+    let buffer = ctx.create_buffer(&Buffer::with_uniform_data(
+        Access::Once,
+        Usage::Write,
+        &offsets,
+    ))?;
+
+    ctx.bind_buffer_range(buffer, 1, 0, 16, Kind::Uniform)?;
+    ctx.bind_buffer_range(buffer, 2, 256, 16, Kind::Uniform)?;
+
+    let points: [f32; 6] = [0.0, 0.0, 4.0, 0.0, 0.0, 4.0];
+    let target = black_screen(ctx)?;
+    let shader = create_shader(ctx, OFFSETS_VS, WHITE_FS)?;
+    let layout = create_vec3_layout(ctx, &points)?;
+
+    ctx.set_program_point_size(true);
+    ctx.draw(target, Primitive::Points, shader, layout, 0, 2)?;
+
+    let img = screenshot(ctx, target)?;
+    let half = img.width / 2;
+    check!(count_pixels(&img, WHITE, 0, half) == 16);
+    check!(count_pixels(&img, WHITE, half, img.width) == 16);
+
+    let misaligned = matches!(
+        ctx.bind_buffer_range(buffer, 1, 4, 16, Kind::Uniform),
+        Err(Error::InvalidOperation(_))
+    );
+    check!(misaligned);
+
+    let out_of_bounds = matches!(
+        ctx.bind_buffer_range(buffer, 1, 256, 512, Kind::Uniform),
+        Err(Error::InvalidOperation(_))
+    );
+    check!(out_of_bounds);
+
+    let empty = matches!(
+        ctx.bind_buffer_range(buffer, 1, 0, 0, Kind::Uniform),
+        Err(Error::InvalidOperation(_))
+    );
+    check!(empty);
+
+    Ok(())
+}
+
+fn create_buffer_from_iter(ctx: &mut impl Context) -> TestResult {
+    #[repr(C)]
+    struct Vertex {
+        position: [f32; 3],
+    }
+    unsafe impl FlatData for Vertex {}
+
+    #[allow(clippy::cast_precision_loss)]
+    let positions = (0..100).map(|i| Vertex {
+        position: [i as f32, 0.0, 1.0],
+    });
+    let buffer = ctx.create_buffer_from_iter(positions, Access::Once, Usage::Write)?;
+
+    check!(ctx.buffer(buffer).map(Native::size) == Some(100 * 12));
+    check!(ctx.buffer_element_size(buffer) == Some(12));
+
+    let mapping = ctx.map_buffer_range(buffer, 0, 100 * 12, MapFlags::READ)?;
+    let read: Option<Vec<f32>> = mapping.as_slice().map(|bytes| {
+        bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+            .collect()
+    });
+    drop(mapping);
+
+    #[allow(clippy::cast_precision_loss)]
+    let expected: Vec<f32> = (0..100).flat_map(|i| [i as f32, 0.0, 1.0]).collect();
+    check!(read.as_deref() == Some(expected.as_slice()));
+
+    let empty =
+        ctx.create_buffer_from_iter(std::iter::empty::<f32>(), Access::Once, Usage::Write)?;
+    check!(ctx.buffer(empty).map(Native::size) == Some(0));
+
+    Ok(())
 }
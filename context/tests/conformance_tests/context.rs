@@ -1,11 +1,35 @@
-use cac_core::math::URect;
+use cac_core::{
+    image::Pixel,
+    math::{Origin, URect},
+};
 
-use cac_context::Context;
+use cac_context::{
+    buffer::{Access, Kind, Usage},
+    shader::{Shader, Stage},
+    vertex_layout::{Components, Stride, VertexAttribute},
+    BarrierFlags, Buffer, ClipDepth, ClipOrigin, Context, Error, Feature, Primitive, VertexLayout,
+};
 
-use crate::{runner::TestCase, TestResult};
+use crate::{
+    draw::{
+        black_screen, count_pixels, create_shader, create_vec3_layout, screenshot, POINT_VS, WHITE,
+        WHITE_FS,
+    },
+    runner::TestCase,
+    TestResult,
+};
 
 pub fn tests() -> Vec<TestCase> {
-    vec![TEST!(viewport_is_window_size)]
+    vec![
+        TEST!(viewport_is_window_size),
+        TEST!(point_coord_origin),
+        TEST!(memory_barrier),
+        TEST!(seamless_cubemaps),
+        TEST!(sample_shading),
+        TEST!(debug_output),
+        TEST!(supports),
+        TEST!(is_lost),
+    ]
 }
 
 fn viewport_is_window_size(ctx: &impl Context) -> TestResult {
@@ -15,3 +39,158 @@ fn viewport_is_window_size(ctx: &impl Context) -> TestResult {
 
     Ok(())
 }
+
+fn point_coord_origin(ctx: &mut impl Context) -> TestResult {
+    //the half of the point sprite with the lower y coordinate is white
+    const HALF_FS: &str = r"
+    #version 430 core
+    out vec4 color;
+
+    void main() {
+        color = gl_PointCoord.y < 0.5 ? vec4(1.0) : vec4(0.0, 0.0, 0.0, 1.0);
+    }
+    ";
+
+    let shader = create_shader(ctx, POINT_VS, HALF_FS)?;
+    let layout = create_vec3_layout(ctx, &[0.0, 0.0, 8.0])?;
+    ctx.set_program_point_size(true);
+
+    //the point covers the 8x8 pixels around the center, the rows of the image start at the bottom
+    let (x, y) = (crate::CONTEXT_WIDTH / 2 - 4, crate::CONTEXT_HEIGHT / 2 - 4);
+    let (bottom, top) = ((x, y), (x, y + 7));
+
+    for (origin, white, black) in [
+        (Origin::TopLeft, top, bottom),
+        (Origin::BottomLeft, bottom, top),
+    ] {
+        ctx.set_point_coord_origin(origin);
+        let target = black_screen(ctx)?;
+        ctx.draw(target, Primitive::Points, shader, layout, 0, 1)?;
+
+        let img = screenshot(ctx, target)?;
+        check!(img.sample(white.0, white.1) == Some(WHITE));
+        check!(img.sample(black.0, black.1) == Some(Pixel::RgbU8([0, 0, 0])));
+    }
+    ctx.set_point_coord_origin(Origin::TopLeft);
+
+    let errors = ctx.poll_errors();
+    check!(errors.is_none());
+
+    Ok(())
+}
+
+fn memory_barrier(ctx: &mut impl Context) -> TestResult {
+    //moves the point from outside of the clip space into the center and makes it visible
+    const MOVE_CS: &str = r"
+    #version 430 core
+    layout(local_size_x = 1) in;
+    layout(std430, binding = 1) buffer Points { float points[]; };
+
+    void main() {
+        points[0] = 0.0;
+        points[1] = 0.0;
+        points[2] = 8.0;
+    }
+    ";
+
+    let points: [f32; 3] = [3.0, 0.0, 1.0];
+    let points = ctx.create_buffer(&Buffer::with_vertex_data(
+        Access::Once,
+        Usage::Write,
+        &points,
+    ))?;
+    let mut layout = VertexLayout::new();
+    layout.push_attributes([VertexAttribute::with_f32(0, Components::Vec3, 0)]);
+    layout.set_buffer(0, points, Stride::Interleaved, 0)?;
+    let layout = ctx.create_layout(&layout)?;
+
+    let compute = [MOVE_CS];
+    let compute = ctx.create_shader(Shader::with_stages(&[Stage::new_compute(&compute)]))?;
+    let work_groups: [u32; 3] = [1, 1, 1];
+    let work_groups = ctx.create_buffer(&Buffer::with_indirect_data(
+        Access::Once,
+        Usage::Write,
+        &work_groups,
+    ))?;
+
+    ctx.bind_buffer_range(points, 1, 0, 12, Kind::Storage)?;
+    ctx.dispatch_compute_indirect(compute, work_groups, 0)?;
+    ctx.memory_barrier(BarrierFlags::VERTEX_ATTRIB_ARRAY);
+
+    let target = black_screen(ctx)?;
+    let shader = create_shader(ctx, POINT_VS, WHITE_FS)?;
+    ctx.set_program_point_size(true);
+    ctx.draw(target, Primitive::Points, shader, layout, 0, 1)?;
+
+    //the draw used the position and size written by the compute shader
+    let img = screenshot(ctx, target)?;
+    check!(count_pixels(&img, WHITE, 0, img.width) == 64);
+
+    ctx.memory_barrier(BarrierFlags::SHADER_STORAGE | BarrierFlags::VERTEX_ATTRIB_ARRAY);
+    ctx.memory_barrier(BarrierFlags::ALL);
+    ctx.memory_barrier(BarrierFlags::empty());
+
+    let errors = ctx.poll_errors();
+    check!(errors.is_none());
+
+    Ok(())
+}
+
+fn seamless_cubemaps(ctx: &mut impl Context) -> TestResult {
+    ctx.set_seamless_cubemaps(true);
+    ctx.set_seamless_cubemaps(false);
+
+    let errors = ctx.poll_errors();
+    check!(errors.is_none());
+
+    Ok(())
+}
+
+fn sample_shading(ctx: &mut impl Context) -> TestResult {
+    ctx.set_sample_shading(Some(1.0));
+    ctx.set_sample_shading(Some(0.5));
+    ctx.set_sample_shading(None);
+
+    let errors = ctx.poll_errors();
+    check!(errors.is_none());
+
+    Ok(())
+}
+
+fn debug_output(ctx: &mut impl Context) -> TestResult {
+    //the test contexts are created with the debug flag
+    let state = ctx.debug_state_string();
+    check!(state.contains("debug output: true"));
+
+    let errors = ctx.poll_errors();
+    check!(errors.is_none());
+
+    Ok(())
+}
+
+fn supports(ctx: &mut impl Context) -> TestResult {
+    //the tests require at least a 4.3 debug context
+    check!(ctx.supports(Feature::ComputeShader));
+    check!(ctx.supports(Feature::DebugOutput));
+
+    let clip_control = ctx
+        .set_clip_control(ClipOrigin::LowerLeft, ClipDepth::NegativeOneToOne)
+        .is_ok();
+    check!(ctx.supports(Feature::ClipControl) == clip_control);
+
+    Ok(())
+}
+
+fn is_lost(ctx: &mut impl Context) -> TestResult {
+    //a reset can't be triggered on purpose, only the healthy context is checked
+    check!(!ctx.is_lost());
+
+    let target = black_screen(ctx)?;
+    let lost_on_clear = matches!(ctx.clear_depth(target), Err(Error::ContextLost));
+    check!(!lost_on_clear);
+
+    ctx.reset();
+    check!(!ctx.is_lost());
+
+    Ok(())
+}
@@ -0,0 +1,1187 @@
+use cac_context::{
+    buffer::{Access, FlatData, Kind, MapFlags, Usage, DRAW_CONSTANTS_BINDING},
+    fence, query,
+    render_target::Native,
+    shader::{Shader, Stage},
+    vertex_layout::{Components, Stride, VertexAttribute},
+    BarrierFlags, Buffer, ClipDepth, ClipOrigin, Context, DrawCommand, Error, LogicOp, Mesh,
+    Primitive, ProvokingVertex, QueryHandle, RenderPass, RenderTarget, RenderTargetHandle,
+    ShaderHandle, VertexLayout, VertexLayoutHandle,
+};
+use cac_core::{
+    image::{Format, Image, Pixel},
+    math::{vec3, Mat4, URect},
+    Color32,
+};
+
+use crate::{logger::take_warnings, runner::TestCase, TestResult};
+
+pub const POINT_VS: &str = r"
+#version 430 core
+//xy is the position, z the size of the point
+layout(location = 0) in vec3 pos;
+
+void main() {
+    gl_Position = vec4(pos.xy, 0.0, 1.0);
+    gl_PointSize = pos.z;
+}
+";
+
+pub const WHITE_FS: &str = r"
+#version 430 core
+out vec4 color;
+
+void main() {
+    color = vec4(1.0);
+}
+";
+
+pub const WHITE: Pixel = Pixel::RgbU8([255, 255, 255]);
+
+pub fn tests() -> Vec<TestCase> {
+    vec![
+        TEST!(program_point_size),
+        TEST!(clip_control_origin),
+        TEST!(simple_layout),
+        TEST!(viewport_stack),
+        TEST!(deleted_layout_buffer),
+        TEST!(color_vertex_data),
+        TEST!(alpha_to_coverage),
+        TEST!(draw_arrays_indirect),
+        TEST!(multi_draw_indirect),
+        TEST!(dispatch_compute_indirect),
+        TEST!(debug_state_string),
+        TEST!(occlusion_query),
+        TEST!(mesh),
+        TEST!(draw_arrays_no_layout),
+        TEST!(depth_range),
+        TEST!(draw_transformed),
+        TEST!(draw_constants),
+        TEST!(clear_depth_only),
+        TEST!(provoking_vertex),
+        TEST!(polygon_offset),
+        TEST!(run_pass),
+        TEST!(logic_op),
+        TEST!(submit_command_list),
+        TEST!(fence),
+    ]
+}
+
+/// Creates a render target covering the whole screen, cleared to black.
+pub fn black_screen(ctx: &mut impl Context) -> anyhow::Result<RenderTargetHandle> {
+    let target = ctx.create_render_target(RenderTarget::with_clear_color(
+        ctx.viewport(),
+        Color32::BLACK,
+    ))?;
+
+    ctx.render_target_mut(target)
+        .ok_or_else(|| anyhow::anyhow!("render target not found"))?
+        .clear();
+
+    Ok(target)
+}
+
+pub fn create_shader(
+    ctx: &mut impl Context,
+    vertex: &str,
+    fragment: &str,
+) -> anyhow::Result<ShaderHandle> {
+    let (vertex, fragment) = ([vertex], [fragment]);
+    let stages = [Stage::new_vertex(&vertex), Stage::new_fragment(&fragment)];
+    Ok(ctx.create_shader(Shader::with_stages(&stages))?)
+}
+
+/// Creates a layout with a single interleaved buffer of vec3s at location 0.
+pub fn create_vec3_layout(
+    ctx: &mut impl Context,
+    data: &[f32],
+) -> anyhow::Result<VertexLayoutHandle> {
+    let buffer = ctx.create_buffer(&Buffer::with_vertex_data(Access::Once, Usage::Write, data))?;
+
+    let mut layout = VertexLayout::new();
+    layout.push_attributes([VertexAttribute::with_f32(0, Components::Vec3, 0)]);
+    layout.set_buffer(0, buffer, Stride::Interleaved, 0)?;
+
+    Ok(ctx.create_layout(&layout)?)
+}
+
+pub fn screenshot(ctx: &impl Context, target: RenderTargetHandle) -> anyhow::Result<Image> {
+    let rt = ctx
+        .render_target(target)
+        .ok_or_else(|| anyhow::anyhow!("render target not found"))?;
+
+    Ok(rt.read_pixels(Format::RgbU8, ctx.viewport())?)
+}
+
+/// Counts the pixels matching the color inside the columns [`start_x`, `end_x`)
+pub fn count_pixels(img: &Image, color: Pixel, start_x: u32, end_x: u32) -> usize {
+    (0..img.height)
+        .flat_map(|y| (start_x..end_x).map(move |x| (x, y)))
+        .filter(|(x, y)| img.sample(*x, *y) == Some(color))
+        .count()
+}
+
+fn program_point_size(ctx: &mut impl Context) -> TestResult {
+    //a small point on the left, a big one on the right
+    #[rustfmt::skip]
+    let points = [
+        -0.5, 0.0, 2.0,
+         0.5, 0.0, 8.0,
+    ];
+
+    let target = black_screen(ctx)?;
+    let shader = create_shader(ctx, POINT_VS, WHITE_FS)?;
+    let layout = create_vec3_layout(ctx, &points)?;
+
+    ctx.set_program_point_size(true);
+    ctx.draw(target, Primitive::Points, shader, layout, 0, 2)?;
+
+    let img = screenshot(ctx, target)?;
+    let half = img.width / 2;
+    let small = count_pixels(&img, WHITE, 0, half);
+    let big = count_pixels(&img, WHITE, half, img.width);
+
+    check!(small == 4);
+    check!(big == 64);
+
+    Ok(())
+}
+
+fn clip_control_origin(ctx: &mut impl Context) -> TestResult {
+    //a point in the upper half of the clip space
+    let point = [0.0, 0.5, 8.0];
+
+    let shader = create_shader(ctx, POINT_VS, WHITE_FS)?;
+    let layout = create_vec3_layout(ctx, &point)?;
+    ctx.set_program_point_size(true);
+
+    //the rows of the screenshot start at the bottom of the screen
+    let upper_half = |img: &Image| {
+        (img.height / 2..img.height)
+            .flat_map(|y| (0..img.width).map(move |x| (x, y)))
+            .filter(|(x, y)| img.sample(*x, *y) == Some(WHITE))
+            .count()
+    };
+
+    let target = black_screen(ctx)?;
+    ctx.draw(target, Primitive::Points, shader, layout, 0, 1)?;
+    let img = screenshot(ctx, target)?;
+    check!(upper_half(&img) == 64);
+
+    match ctx.set_clip_control(ClipOrigin::UpperLeft, ClipDepth::ZeroToOne) {
+        Ok(()) => {}
+        //clip control is optional on 4.3 contexts
+        Err(Error::InvalidContext(_)) => return Ok(()),
+        Err(e) => return Err(e.into()),
+    }
+
+    let target = black_screen(ctx)?;
+    ctx.draw(target, Primitive::Points, shader, layout, 0, 1)?;
+    let img = screenshot(ctx, target)?;
+    check!(upper_half(&img) == 0);
+
+    Ok(())
+}
+
+fn simple_layout(ctx: &mut impl Context) -> TestResult {
+    let point: [f32; 3] = [0.0, 0.0, 4.0];
+
+    let buffer = ctx.create_buffer(&Buffer::with_vertex_data(
+        Access::Once,
+        Usage::Write,
+        &point,
+    ))?;
+    let layout = ctx.create_simple_layout(
+        buffer,
+        &[VertexAttribute::with_f32(0, Components::Vec3, 0)],
+        Stride::Interleaved,
+    )?;
+
+    let target = black_screen(ctx)?;
+    let shader = create_shader(ctx, POINT_VS, WHITE_FS)?;
+
+    ctx.set_program_point_size(true);
+    ctx.draw(target, Primitive::Points, shader, layout, 0, 1)?;
+
+    let img = screenshot(ctx, target)?;
+    check!(count_pixels(&img, WHITE, 0, img.width) == 16);
+
+    Ok(())
+}
+
+fn viewport_stack(ctx: &mut impl Context) -> TestResult {
+    let point = [0.0, 0.0, 2.0];
+    let original = ctx.viewport();
+    let bottom_left = URect::new(0, 0, original.width / 2, original.height / 2);
+
+    let target = black_screen(ctx)?;
+    let shader = create_shader(ctx, POINT_VS, WHITE_FS)?;
+    let layout = create_vec3_layout(ctx, &point)?;
+    ctx.set_program_point_size(true);
+
+    ctx.push_viewport(bottom_left)?;
+    check!(ctx.viewport() == bottom_left);
+
+    //the center of the pushed viewport
+    ctx.draw(target, Primitive::Points, shader, layout, 0, 1)?;
+
+    check!(ctx.pop_viewport() == Some(bottom_left));
+    check!(ctx.viewport() == original);
+    check!(ctx.pop_viewport().is_none());
+
+    let img = screenshot(ctx, target)?;
+    let black = Image::with_color32(img.width, img.height, Color32::BLACK, Format::RgbU8)?;
+    let (x, y) = (bottom_left.width / 2 - 1, bottom_left.height / 2 - 1);
+    check!(img.difference_region(&black, 0.1)? == Some(URect::new(x, y, 2, 2)));
+
+    //popping restores the viewport of the bound target, not the one of the context
+    let left = URect::new(0, 0, original.width / 2, original.height);
+    let half = ctx.create_render_target(RenderTarget::with_clear_color(left, Color32::BLACK))?;
+    ctx.render_target_mut(half)
+        .ok_or_else(|| anyhow::anyhow!("render target not found"))?
+        .clear();
+    ctx.push_viewport(bottom_left)?;
+    ctx.pop_viewport();
+    ctx.draw(half, Primitive::Points, shader, layout, 0, 1)?;
+
+    let img = screenshot(ctx, target)?;
+    let (x, y) = (left.width / 2 - 1, left.height / 2 - 1);
+    check!(img.difference_region(&black, 0.1)? == Some(URect::new(x, y, 2, 2)));
+
+    Ok(())
+}
+
+fn deleted_layout_buffer(ctx: &mut impl Context) -> TestResult {
+    let point: [f32; 3] = [0.0, 0.0, 1.0];
+
+    let target = black_screen(ctx)?;
+    let shader = create_shader(ctx, POINT_VS, WHITE_FS)?;
+    let buffer = ctx.create_buffer(&Buffer::with_vertex_data(
+        Access::Once,
+        Usage::Write,
+        &point,
+    ))?;
+
+    let mut layout = VertexLayout::new();
+    layout.push_attributes([VertexAttribute::with_f32(0, Components::Vec3, 0)]);
+    layout.set_buffer(0, buffer, Stride::Interleaved, 0)?;
+    let layout = ctx.create_layout(&layout)?;
+
+    ctx.draw(target, Primitive::Points, shader, layout, 0, 1)?;
+    let errors = ctx.poll_errors();
+    check!(errors.is_none());
+
+    let reported = |warnings: Vec<String>| {
+        warnings
+            .iter()
+            .any(|w| w.contains("references the deleted buffer"))
+    };
+    check!(!reported(take_warnings()));
+
+    ctx.delete_buffer(buffer)?;
+    check!(ctx.buffer(buffer).is_none());
+    check!(matches!(
+        ctx.delete_buffer(buffer),
+        Err(cac_context::Error::ResourceNotFound)
+    ));
+
+    //debug builds log a warning about the deleted buffer, the draw itself still succeeds
+    check!(ctx
+        .draw(target, Primitive::Points, shader, layout, 0, 1)
+        .is_ok());
+    check!(ctx.layout(layout).is_some());
+    check!(reported(take_warnings()) == cfg!(debug_assertions));
+
+    Ok(())
+}
+
+fn color_vertex_data(ctx: &mut impl Context) -> TestResult {
+    const COLOR_VS: &str = r"
+    #version 430 core
+    layout(location = 0) in vec3 pos;
+    layout(location = 1) in vec4 color;
+    out vec4 v_color;
+
+    void main() {
+        gl_Position = vec4(pos.xy, 0.0, 1.0);
+        gl_PointSize = pos.z;
+        v_color = color;
+    }
+    ";
+    const COLOR_FS: &str = r"
+    #version 430 core
+    in vec4 v_color;
+    out vec4 color;
+
+    void main() {
+        color = v_color;
+    }
+    ";
+
+    let points: [f32; 6] = [-0.5, 0.0, 2.0, 0.5, 0.0, 2.0];
+    let colors = [Color32::RED, Color32::BLUE];
+
+    let target = black_screen(ctx)?;
+    let shader = create_shader(ctx, COLOR_VS, COLOR_FS)?;
+
+    let positions = ctx.create_buffer(&Buffer::with_vertex_data(
+        Access::Once,
+        Usage::Write,
+        &points,
+    ))?;
+    let color_data = Color32::slice_as_f32(&colors);
+    let colors = ctx.create_buffer(&Buffer::with_vertex_data(
+        Access::Once,
+        Usage::Write,
+        color_data,
+    ))?;
+
+    let mut layout = VertexLayout::new();
+    layout.push_attributes([VertexAttribute::with_f32(0, Components::Vec3, 0)]);
+    layout.push_attributes([VertexAttribute::with_f32(1, Components::Vec4, 0)]);
+    layout.set_buffer(0, positions, Stride::Interleaved, 0)?;
+    layout.set_buffer(1, colors, Stride::Interleaved, 0)?;
+    let layout = ctx.create_layout(&layout)?;
+
+    ctx.set_program_point_size(true);
+    ctx.draw(target, Primitive::Points, shader, layout, 0, 2)?;
+
+    let img = screenshot(ctx, target)?;
+    let half = img.width / 2;
+    check!(count_pixels(&img, Pixel::RgbU8([255, 0, 0]), 0, half) == 4);
+    check!(count_pixels(&img, Pixel::RgbU8([0, 0, 255]), half, img.width) == 4);
+
+    Ok(())
+}
+
+fn alpha_to_coverage(ctx: &mut impl Context) -> TestResult {
+    const TRANSLUCENT_FS: &str = r"
+    #version 430 core
+    out vec4 color;
+
+    void main() {
+        color = vec4(1.0, 1.0, 1.0, 0.25);
+    }
+    ";
+
+    let target = black_screen(ctx)?;
+    let shader = create_shader(ctx, POINT_VS, TRANSLUCENT_FS)?;
+    let layout = create_vec3_layout(ctx, &[0.0, 0.0, 4.0])?;
+
+    //the tracked and the queried state, reported by the debug state string
+    let alpha_to_coverage = |report: String| {
+        report
+            .lines()
+            .find_map(|l| l.trim().strip_prefix("alpha to coverage: "))
+            .map(str::to_owned)
+    };
+
+    //the screen isn't multisampled, so the coverage of the point doesn't change
+    ctx.set_program_point_size(true);
+    ctx.set_alpha_to_coverage(true);
+    let enabled = alpha_to_coverage(ctx.debug_state_string());
+    check!(enabled.as_deref() == Some("true (enabled true)"));
+    ctx.draw(target, Primitive::Points, shader, layout, 0, 1)?;
+    ctx.set_alpha_to_coverage(false);
+    let disabled = alpha_to_coverage(ctx.debug_state_string());
+    check!(disabled.as_deref() == Some("false (enabled false)"));
+
+    let errors = ctx.poll_errors();
+    check!(errors.is_none());
+
+    let img = screenshot(ctx, target)?;
+    check!(count_pixels(&img, WHITE, 0, img.width) == 16);
+
+    Ok(())
+}
+
+fn draw_arrays_indirect(ctx: &mut impl Context) -> TestResult {
+    //count, instance count, first, base instance
+    let commands: [u32; 8] = [0, 0, 0, 0, 1, 1, 1, 0];
+    let points: [f32; 6] = [-0.5, 0.0, 2.0, 0.5, 0.0, 2.0];
+
+    let target = black_screen(ctx)?;
+    let shader = create_shader(ctx, POINT_VS, WHITE_FS)?;
+    let layout = create_vec3_layout(ctx, &points)?;
+    let commands = ctx.create_buffer(&Buffer::with_indirect_data(
+        Access::Once,
+        Usage::Write,
+        &commands,
+    ))?;
+
+    //only draws the second point, on the right side of the screen
+    ctx.set_program_point_size(true);
+    ctx.draw_arrays_indirect(target, Primitive::Points, shader, layout, commands, 16)?;
+
+    let errors = ctx.poll_errors();
+    check!(errors.is_none());
+
+    let img = screenshot(ctx, target)?;
+    let half = img.width / 2;
+    check!(count_pixels(&img, WHITE, 0, half) == 0);
+    check!(count_pixels(&img, WHITE, half, img.width) == 4);
+
+    for offset in [18, 20] {
+        let invalid = matches!(
+            ctx.draw_arrays_indirect(target, Primitive::Points, shader, layout, commands, offset),
+            Err(Error::InvalidOperation(_))
+        );
+        check!(invalid);
+    }
+
+    Ok(())
+}
+
+fn multi_draw_indirect(ctx: &mut impl Context) -> TestResult {
+    //count, instance count, first, base instance and one u32 of padding per draw
+    let commands: [u32; 15] = [1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 2, 0, 0];
+    let points: [f32; 9] = [-0.5, 0.0, 2.0, 0.0, 0.5, 2.0, 0.5, 0.0, 2.0];
+
+    let target = black_screen(ctx)?;
+    let shader = create_shader(ctx, POINT_VS, WHITE_FS)?;
+    let layout = create_vec3_layout(ctx, &points)?;
+    let commands = ctx.create_buffer(&Buffer::with_indirect_data(
+        Access::Once,
+        Usage::Write,
+        &commands,
+    ))?;
+
+    //the second draw is empty, the first and third point are drawn
+    ctx.set_program_point_size(true);
+    ctx.multi_draw_indirect(target, Primitive::Points, shader, layout, commands, 3, 20)?;
+
+    let errors = ctx.poll_errors();
+    check!(errors.is_none());
+
+    let img = screenshot(ctx, target)?;
+    let half = img.width / 2;
+    check!(count_pixels(&img, WHITE, 0, half) == 4);
+    check!(count_pixels(&img, WHITE, half, img.width) == 4);
+
+    let misaligned = matches!(
+        ctx.multi_draw_indirect(target, Primitive::Points, shader, layout, commands, 2, 18),
+        Err(Error::InvalidOperation(_))
+    );
+    check!(misaligned);
+
+    //a fourth draw would end past the 60 bytes of the buffer
+    let overflow = matches!(
+        ctx.multi_draw_indirect(target, Primitive::Points, shader, layout, commands, 4, 20),
+        Err(Error::InvalidOperation(_))
+    );
+    check!(overflow);
+
+    Ok(())
+}
+
+fn dispatch_compute_indirect(ctx: &mut impl Context) -> TestResult {
+    const COUNT_CS: &str = r"
+    #version 430 core
+    layout(local_size_x = 1) in;
+    layout(std430, binding = 1) buffer Counter { uint invocations; };
+
+    void main() {
+        atomicAdd(invocations, 1);
+    }
+    ";
+
+    let compute = [COUNT_CS];
+    let shader = ctx.create_shader(Shader::with_stages(&[Stage::new_compute(&compute)]))?;
+
+    let counter: [u32; 1] = [0];
+    let counter = ctx.create_buffer(&Buffer::with_vertex_data(
+        Access::Frequent,
+        Usage::Write,
+        &counter,
+    ))?;
+    ctx.bind_buffer_range(counter, 1, 0, 4, Kind::Storage)?;
+
+    //the work group counts at the byte offset 20 end with the buffer
+    let work_groups: [u32; 8] = [0, 0, 0, 0, 0, 2, 3, 1];
+    let work_groups = ctx.create_buffer(&Buffer::with_indirect_data(
+        Access::Once,
+        Usage::Write,
+        &work_groups,
+    ))?;
+
+    ctx.dispatch_compute_indirect(shader, work_groups, 20)?;
+    ctx.memory_barrier(BarrierFlags::BUFFER_UPDATE);
+
+    let mapping = ctx.map_buffer_range(counter, 0, 4, MapFlags::READ)?;
+    let invocations = mapping.as_slice().map(<[u8]>::to_vec);
+    drop(mapping);
+    check!(invocations == Some(6u32.to_ne_bytes().to_vec()));
+
+    let misaligned = matches!(
+        ctx.dispatch_compute_indirect(shader, work_groups, 18),
+        Err(Error::InvalidOperation(_))
+    );
+    check!(misaligned);
+
+    let overflow = matches!(
+        ctx.dispatch_compute_indirect(shader, work_groups, 24),
+        Err(Error::InvalidOperation(_))
+    );
+    check!(overflow);
+
+    let errors = ctx.poll_errors();
+    check!(errors.is_none());
+
+    Ok(())
+}
+
+fn debug_state_string(ctx: &mut impl Context) -> TestResult {
+    let target = black_screen(ctx)?;
+    let shader = create_shader(ctx, POINT_VS, WHITE_FS)?;
+    let layout = create_vec3_layout(ctx, &[0.0, 0.0, 1.0])?;
+    ctx.draw(target, Primitive::Points, shader, layout, 0, 1)?;
+
+    let report = ctx.debug_state_string();
+
+    //the tracked shader matches the program that is actually in use
+    let program = report
+        .lines()
+        .find_map(|l| l.trim().strip_prefix("shader: program "))
+        .and_then(|l| l.split_whitespace().next());
+
+    if let Some(program) = program {
+        check!(report.contains(&format!("(current program {program})")));
+    } else {
+        error!(format!("bound shader missing in the report: {report}"));
+    }
+    check!(report.contains("layout: vertex array"));
+    check!(report.contains("program point size"));
+
+    Ok(())
+}
+
+/// Polls the query result until it is available.
+pub fn wait_for_query(ctx: &impl Context, query: QueryHandle) -> anyhow::Result<u64> {
+    for _ in 0..1000 {
+        if let Some(result) = ctx.query_result(query) {
+            return Ok(result);
+        }
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+
+    Err(anyhow::anyhow!("query result not available"))
+}
+
+fn occlusion_query(ctx: &mut impl Context) -> TestResult {
+    //the first point is outside of the clip space
+    let points: [f32; 6] = [3.0, 0.0, 2.0, 0.0, 0.0, 2.0];
+
+    let target = black_screen(ctx)?;
+    let shader = create_shader(ctx, POINT_VS, WHITE_FS)?;
+    let layout = create_vec3_layout(ctx, &points)?;
+    ctx.set_program_point_size(true);
+
+    ctx.begin_query(query::Kind::SamplesPassed)?;
+    check!(matches!(
+        ctx.begin_query(query::Kind::SamplesPassed),
+        Err(Error::InvalidOperation(_))
+    ));
+    ctx.draw(target, Primitive::Points, shader, layout, 0, 1)?;
+    let hidden = ctx.end_query()?;
+
+    ctx.begin_query(query::Kind::SamplesPassed)?;
+    ctx.draw(target, Primitive::Points, shader, layout, 1, 1)?;
+    let visible = ctx.end_query()?;
+
+    ctx.begin_query(query::Kind::PrimitivesGenerated)?;
+    ctx.draw(target, Primitive::Points, shader, layout, 0, 2)?;
+    let generated = ctx.end_query()?;
+
+    check!(ctx.end_query().is_err());
+    check!(wait_for_query(ctx, hidden)? == 0);
+    check!(wait_for_query(ctx, visible)? == 4);
+    check!(wait_for_query(ctx, generated)? == 2);
+
+    ctx.delete_query(hidden)?;
+    check!(ctx.query_result(hidden).is_none());
+
+    let errors = ctx.poll_errors();
+    check!(errors.is_none());
+
+    Ok(())
+}
+
+fn mesh(ctx: &mut impl Context) -> TestResult {
+    //quad in the center, as a triangle strip
+    let quad: [f32; 12] = [
+        -0.1, -0.1, 0.0, 0.1, -0.1, 0.0, -0.1, 0.1, 0.0, 0.1, 0.1, 0.0,
+    ];
+
+    let shader = create_shader(ctx, POINT_VS, WHITE_FS)?;
+    let layout = create_vec3_layout(ctx, &quad)?;
+
+    let target = black_screen(ctx)?;
+    ctx.draw(target, Primitive::TriangleStrip, shader, layout, 0, 4)?;
+    let manual = screenshot(ctx, target)?;
+
+    let target = black_screen(ctx)?;
+    let mesh = Mesh::new(layout, Primitive::TriangleStrip, 4);
+    mesh.draw(ctx, target, shader)?;
+    let img = screenshot(ctx, target)?;
+
+    check!(count_pixels(&img, WHITE, 0, img.width) > 0);
+    check!(img.difference_region(&manual, 0.0)?.is_none());
+
+    Ok(())
+}
+
+fn draw_arrays_no_layout(ctx: &mut impl Context) -> TestResult {
+    //triangle in the left half of the screen, generated from the vertex id
+    const VERTEX_ID_VS: &str = r"
+    #version 430 core
+
+    void main() {
+        vec2 positions[3] = vec2[](vec2(-1.0, -1.0), vec2(0.0, -1.0), vec2(-1.0, 1.0));
+        gl_Position = vec4(positions[gl_VertexID], 0.0, 1.0);
+    }
+    ";
+
+    let shader = create_shader(ctx, VERTEX_ID_VS, WHITE_FS)?;
+    let target = black_screen(ctx)?;
+    ctx.draw_arrays_no_layout(target, Primitive::Triangles, shader, 3)?;
+
+    let img = screenshot(ctx, target)?;
+    let half = img.width / 2;
+    let left = count_pixels(&img, WHITE, 0, half);
+    let right = count_pixels(&img, WHITE, half, img.width);
+
+    check!(left > 0);
+    check!(right == 0);
+
+    let errors = ctx.poll_errors();
+    check!(errors.is_none());
+
+    Ok(())
+}
+
+fn depth_range(ctx: &mut impl Context) -> TestResult {
+    //point at the far plane, writing its window depth as the color
+    const FAR_VS: &str = r"
+    #version 430 core
+    layout(location = 0) in vec3 pos;
+
+    void main() {
+        gl_Position = vec4(pos.xy, 1.0, 1.0);
+        gl_PointSize = pos.z;
+    }
+    ";
+    const DEPTH_FS: &str = r"
+    #version 430 core
+    out vec4 color;
+
+    void main() {
+        color = vec4(vec3(gl_FragCoord.z), 1.0);
+    }
+    ";
+
+    let point: [f32; 3] = [0.0, 0.0, 4.0];
+    let shader = create_shader(ctx, FAR_VS, DEPTH_FS)?;
+    let layout = create_vec3_layout(ctx, &point)?;
+    ctx.set_program_point_size(true);
+
+    let depth_at_center = |ctx: &mut _, target| -> anyhow::Result<u8> {
+        let img = screenshot(ctx, target)?;
+        match img.sample(img.width / 2, img.height / 2) {
+            Some(Pixel::RgbU8([depth, ..])) => Ok(depth),
+            pixel => Err(anyhow::anyhow!("unexpected pixel {pixel:?}")),
+        }
+    };
+
+    let target = black_screen(ctx)?;
+    ctx.draw(target, Primitive::Points, shader, layout, 0, 1)?;
+    let full = depth_at_center(ctx, target)?;
+    check!(full == 255);
+
+    ctx.set_depth_range(0.0, 0.5);
+    let target = black_screen(ctx)?;
+    ctx.draw(target, Primitive::Points, shader, layout, 0, 1)?;
+    let half = depth_at_center(ctx, target)?;
+    check!((127..=128).contains(&half));
+
+    Ok(())
+}
+
+fn draw_transformed(ctx: &mut impl Context) -> TestResult {
+    const TRANSFORM_VS: &str = r"
+    #version 430 core
+    layout(location = 0) in vec3 pos;
+    uniform mat4 u_transform;
+
+    void main() {
+        gl_Position = u_transform * vec4(pos, 1.0);
+    }
+    ";
+
+    //triangle around the center
+    let triangle: [f32; 9] = [-0.2, -0.2, 0.0, 0.2, -0.2, 0.0, 0.0, 0.2, 0.0];
+    let shader = create_shader(ctx, TRANSFORM_VS, WHITE_FS)?;
+    let layout = create_vec3_layout(ctx, &triangle)?;
+
+    let target = black_screen(ctx)?;
+    let transform = Mat4::from_translation(vec3(0.5, 0.0, 0.0));
+    ctx.draw_transformed(
+        target,
+        Primitive::Triangles,
+        shader,
+        layout,
+        0,
+        3,
+        transform,
+    )?;
+
+    let img = screenshot(ctx, target)?;
+    let half = img.width / 2;
+    let left = count_pixels(&img, WHITE, 0, half);
+    let right = count_pixels(&img, WHITE, half, img.width);
+
+    check!(left == 0);
+    check!(right > 0);
+
+    //a failed draw keeps the previous transform
+    let failed = ctx.draw_transformed(
+        target,
+        Primitive::Triangles,
+        shader,
+        layout,
+        0,
+        usize::MAX,
+        Mat4::from_translation(vec3(-0.5, 0.0, 0.0)),
+    );
+    check!(matches!(failed, Err(Error::ConversionFailed(_))));
+    let target = black_screen(ctx)?;
+    ctx.draw(target, Primitive::Triangles, shader, layout, 0, 3)?;
+    let img = screenshot(ctx, target)?;
+    check!(count_pixels(&img, WHITE, 0, half) == 0);
+
+    //shaders without the uniform still draw
+    let target = black_screen(ctx)?;
+    let shader = create_shader(ctx, POINT_VS, WHITE_FS)?;
+    ctx.draw_transformed(
+        target,
+        Primitive::Triangles,
+        shader,
+        layout,
+        0,
+        3,
+        transform,
+    )?;
+    let img = screenshot(ctx, target)?;
+    check!(count_pixels(&img, WHITE, 0, img.width) > 0);
+
+    Ok(())
+}
+
+fn draw_constants(ctx: &mut impl Context) -> TestResult {
+    const CONSTANTS_VS: &str = r"
+    #version 430 core
+    layout(location = 0) in vec3 pos;
+    layout(std140, binding = 0) uniform DrawConstants {
+        vec4 offset;
+    };
+
+    void main() {
+        gl_Position = vec4(pos.xy + offset.xy, 0.0, 1.0);
+        gl_PointSize = pos.z;
+    }
+    ";
+
+    #[repr(C)]
+    struct Constants {
+        offset: [f32; 4],
+    }
+    unsafe impl FlatData for Constants {}
+
+    #[repr(C)]
+    struct TooLarge([f32; 128]);
+    unsafe impl FlatData for TooLarge {}
+
+    let point: [f32; 3] = [0.0, 0.0, 4.0];
+    let shader = create_shader(ctx, CONSTANTS_VS, WHITE_FS)?;
+    let layout = create_vec3_layout(ctx, &point)?;
+    ctx.set_program_point_size(true);
+
+    let target = black_screen(ctx)?;
+    let left = Constants {
+        offset: [-0.5, 0.0, 0.0, 0.0],
+    };
+    ctx.set_draw_constants(&left)?;
+    ctx.draw(target, Primitive::Points, shader, layout, 0, 1)?;
+
+    let right = Constants {
+        offset: [0.5, 0.0, 0.0, 0.0],
+    };
+    ctx.set_draw_constants(&right)?;
+    ctx.draw(target, Primitive::Points, shader, layout, 0, 1)?;
+
+    let img = screenshot(ctx, target)?;
+    let half = img.width / 2;
+    check!(count_pixels(&img, WHITE, 0, half) == 16);
+    check!(count_pixels(&img, WHITE, half, img.width) == 16);
+
+    //wraps around the ring
+    for _ in 0..200 {
+        ctx.set_draw_constants(&left)?;
+    }
+
+    let too_large = matches!(
+        ctx.set_draw_constants(&TooLarge([0.0; 128])),
+        Err(Error::InvalidOperation(_))
+    );
+    check!(too_large);
+
+    //the binding point is taken by a buffer that is deleted by the reset
+    let zeros = ctx.create_buffer(&Buffer::with_uniform_data(
+        Access::Once,
+        Usage::Write,
+        &[0.0_f32; 4],
+    ))?;
+    ctx.bind_buffer_range(zeros, DRAW_CONSTANTS_BINDING, 0, 16, Kind::Uniform)?;
+    ctx.reset();
+
+    //the last constants are bound again
+    let shader = create_shader(ctx, CONSTANTS_VS, WHITE_FS)?;
+    let layout = create_vec3_layout(ctx, &point)?;
+    ctx.set_program_point_size(true);
+    let target = black_screen(ctx)?;
+    ctx.draw(target, Primitive::Points, shader, layout, 0, 1)?;
+
+    let img = screenshot(ctx, target)?;
+    check!(count_pixels(&img, WHITE, 0, half) == 16);
+    check!(count_pixels(&img, WHITE, half, img.width) == 0);
+
+    Ok(())
+}
+
+fn clear_depth_only(ctx: &mut impl Context) -> TestResult {
+    const RED_FS: &str = r"
+    #version 430 core
+    out vec4 color;
+
+    void main() {
+        color = vec4(1.0, 0.0, 0.0, 1.0);
+    }
+    ";
+    const RED: Pixel = Pixel::RgbU8([255, 0, 0]);
+
+    let points: [f32; 3] = [0.0, 0.0, 4.0];
+    let white = create_shader(ctx, POINT_VS, WHITE_FS)?;
+    let red = create_shader(ctx, POINT_VS, RED_FS)?;
+    let layout = create_vec3_layout(ctx, &points)?;
+    ctx.set_program_point_size(true);
+    ctx.set_depth_test(true);
+
+    let target = black_screen(ctx)?;
+    ctx.clear_depth(target)?;
+
+    //the second point has the same depth as the first one and fails the depth test
+    ctx.draw(target, Primitive::Points, white, layout, 0, 1)?;
+    ctx.draw(target, Primitive::Points, red, layout, 0, 1)?;
+    let img = screenshot(ctx, target)?;
+    check!(count_pixels(&img, WHITE, 0, img.width) == 16);
+    check!(count_pixels(&img, RED, 0, img.width) == 0);
+
+    //after clearing the depth it passes, while the color is kept
+    ctx.clear_depth(target)?;
+    let img = screenshot(ctx, target)?;
+    check!(count_pixels(&img, WHITE, 0, img.width) == 16);
+
+    ctx.draw(target, Primitive::Points, red, layout, 0, 1)?;
+    let img = screenshot(ctx, target)?;
+    check!(count_pixels(&img, WHITE, 0, img.width) == 0);
+    check!(count_pixels(&img, RED, 0, img.width) == 16);
+    ctx.set_depth_test(false);
+
+    ctx.clear_color_only(target)?;
+    let img = screenshot(ctx, target)?;
+    check!(count_pixels(&img, RED, 0, img.width) == 0);
+
+    Ok(())
+}
+
+fn provoking_vertex(ctx: &mut impl Context) -> TestResult {
+    const FLAT_VS: &str = r"
+    #version 430 core
+    layout(location = 0) in vec3 pos;
+    flat out vec4 v_color;
+
+    const vec4 COLORS[3] = vec4[3](
+        vec4(1.0, 0.0, 0.0, 1.0),
+        vec4(0.0, 1.0, 0.0, 1.0),
+        vec4(0.0, 0.0, 1.0, 1.0)
+    );
+
+    void main() {
+        gl_Position = vec4(pos, 1.0);
+        v_color = COLORS[gl_VertexID];
+    }
+    ";
+    const FLAT_FS: &str = r"
+    #version 430 core
+    flat in vec4 v_color;
+    out vec4 color;
+
+    void main() {
+        color = v_color;
+    }
+    ";
+
+    let triangle: [f32; 9] = [-0.5, -0.5, 0.0, 0.5, -0.5, 0.0, 0.0, 0.5, 0.0];
+    let shader = create_shader(ctx, FLAT_VS, FLAT_FS)?;
+    let layout = create_vec3_layout(ctx, &triangle)?;
+
+    //the center of the face has the color of the provoking vertex
+    for (mode, expected) in [
+        (ProvokingVertex::First, Pixel::RgbU8([255, 0, 0])),
+        (ProvokingVertex::Last, Pixel::RgbU8([0, 0, 255])),
+    ] {
+        ctx.set_provoking_vertex(mode);
+        let target = black_screen(ctx)?;
+        ctx.draw(target, Primitive::Triangles, shader, layout, 0, 3)?;
+
+        let img = screenshot(ctx, target)?;
+        let center = img.sample(img.width / 2, img.height / 2);
+        check!(center == Some(expected));
+    }
+
+    Ok(())
+}
+
+fn polygon_offset(ctx: &mut impl Context) -> TestResult {
+    const DEPTH_VS: &str = r"
+    #version 430 core
+    layout(location = 0) in vec3 pos;
+
+    void main() {
+        gl_Position = vec4(pos, 1.0);
+    }
+    ";
+    //writes the window depth as the color
+    const DEPTH_FS: &str = r"
+    #version 430 core
+    out vec4 color;
+
+    void main() {
+        color = vec4(vec3(gl_FragCoord.z), 1.0);
+    }
+    ";
+
+    let triangle: [f32; 9] = [-0.5, -0.5, 0.0, 0.5, -0.5, 0.0, 0.0, 0.5, 0.0];
+    let shader = create_shader(ctx, DEPTH_VS, DEPTH_FS)?;
+    let layout = create_vec3_layout(ctx, &triangle)?;
+
+    let mut depths = Vec::new();
+    for (factor, units) in [(0.0, 0.0), (0.0, -1_000_000.0), (0.0, 0.0)] {
+        ctx.set_polygon_offset(factor, units);
+        let target = black_screen(ctx)?;
+        ctx.draw(target, Primitive::Triangles, shader, layout, 0, 3)?;
+
+        let img = screenshot(ctx, target)?;
+        match img.sample(img.width / 2, img.height / 2) {
+            Some(Pixel::RgbU8([depth, ..])) => depths.push(depth),
+            pixel => error!(format!("unexpected pixel {pixel:?}")),
+        }
+    }
+
+    //pulled towards the viewer, and back once the offset is disabled
+    check!(depths[1] < depths[0]);
+    check!(depths[2] == depths[0]);
+
+    Ok(())
+}
+
+fn run_pass(ctx: &mut impl Context) -> TestResult {
+    const COLOR_FS: &str = r"
+    #version 430 core
+    out vec4 color;
+
+    void main() {
+        color = vec4(1.0, 0.0, 0.0, 1.0);
+    }
+    ";
+
+    let points: [f32; 6] = [-0.5, 0.0, 4.0, 0.5, 0.0, 4.0];
+    let white = create_shader(ctx, POINT_VS, WHITE_FS)?;
+    let red = create_shader(ctx, POINT_VS, COLOR_FS)?;
+    let layout = create_vec3_layout(ctx, &points)?;
+    ctx.set_program_point_size(true);
+
+    let left = Mesh::new(layout, Primitive::Points, 1);
+    let right = Mesh { start: 1, ..left };
+
+    let target = black_screen(ctx)?;
+    left.draw(ctx, target, white)?;
+    right.draw(ctx, target, red)?;
+    let manual = screenshot(ctx, target)?;
+
+    //leftovers of the manual draws are cleared by the pass
+    let pass = RenderPass::new(target).with_clear();
+    ctx.run_pass(
+        &pass,
+        &[DrawCommand::new(white, left), DrawCommand::new(red, right)],
+    )?;
+    let img = screenshot(ctx, target)?;
+
+    check!(count_pixels(&img, WHITE, 0, img.width) == 16);
+    check!(img.difference_region(&manual, 0.0)?.is_none());
+
+    //the viewport and depth range only apply to the draws of the pass
+    let viewport = ctx.viewport();
+    let depth_range = |report: String| {
+        report
+            .lines()
+            .find(|l| l.contains("depth range"))
+            .map(str::to_owned)
+    };
+    let before = depth_range(ctx.debug_state_string());
+    let pass = pass
+        .with_viewport(URect::new(0, 0, viewport.width / 2, viewport.height))
+        .with_depth_range(0.5, 1.0);
+    ctx.run_pass(&pass, &[DrawCommand::new(white, left)])?;
+    check!(ctx.viewport() == viewport);
+    let after = depth_range(ctx.debug_state_string());
+    check!(after.is_some() && after == before);
+
+    let img = screenshot(ctx, target)?;
+    //the point moved from a quarter to an eighth of the width
+    check!(count_pixels(&img, WHITE, 0, img.width) == 16);
+    check!(count_pixels(&img, WHITE, 0, img.width / 4 - 8) == 16);
+
+    //the red point at the same depth fails the depth test and the white one stays in front
+    let depth_test = |report: String| {
+        report
+            .lines()
+            .find(|l| l.contains("depth test"))
+            .map(str::to_owned)
+    };
+    let before = depth_test(ctx.debug_state_string());
+    ctx.clear_depth(target)?;
+    let pass = RenderPass::new(target).with_clear().with_depth_test(true);
+    ctx.run_pass(
+        &pass,
+        &[DrawCommand::new(white, left), DrawCommand::new(red, left)],
+    )?;
+    let after = depth_test(ctx.debug_state_string());
+    check!(after.is_some() && after == before);
+
+    let img = screenshot(ctx, target)?;
+    check!(count_pixels(&img, WHITE, 0, img.width) == 16);
+
+    Ok(())
+}
+
+fn logic_op(ctx: &mut impl Context) -> TestResult {
+    //rubber band rect over a blue background
+    let quad: [f32; 12] = [
+        -0.5, -0.5, 0.0, 0.5, -0.5, 0.0, -0.5, 0.5, 0.0, 0.5, 0.5, 0.0,
+    ];
+    let shader = create_shader(ctx, POINT_VS, WHITE_FS)?;
+    let layout = create_vec3_layout(ctx, &quad)?;
+
+    let target = ctx.create_render_target(RenderTarget::with_clear_color(
+        ctx.viewport(),
+        Color32::BLUE,
+    ))?;
+    ctx.render_target_mut(target)
+        .ok_or_else(|| anyhow::anyhow!("render target not found"))?
+        .clear();
+    let background = screenshot(ctx, target)?;
+
+    ctx.set_logic_op(Some(LogicOp::Xor));
+    ctx.draw(target, Primitive::TriangleStrip, shader, layout, 0, 4)?;
+
+    //white xor blue is yellow
+    let img = screenshot(ctx, target)?;
+    let center = img.sample(img.width / 2, img.height / 2);
+    check!(center == Some(Pixel::RgbU8([255, 255, 0])));
+
+    //drawing it again erases it
+    ctx.draw(target, Primitive::TriangleStrip, shader, layout, 0, 4)?;
+    let img = screenshot(ctx, target)?;
+    check!(img.difference_region(&background, 0.0)?.is_none());
+
+    ctx.set_logic_op(None);
+    ctx.draw(target, Primitive::TriangleStrip, shader, layout, 0, 4)?;
+    let img = screenshot(ctx, target)?;
+    let center = img.sample(img.width / 2, img.height / 2);
+    check!(center == Some(WHITE));
+
+    Ok(())
+}
+
+fn submit_command_list(ctx: &mut impl Context) -> TestResult {
+    let points: [f32; 6] = [-0.5, 0.0, 4.0, 0.5, 0.0, 4.0];
+    let shader = create_shader(ctx, POINT_VS, WHITE_FS)?;
+    let layout = create_vec3_layout(ctx, &points)?;
+    ctx.set_program_point_size(true);
+    let mesh = Mesh::new(layout, Primitive::Points, 2);
+
+    let target = black_screen(ctx)?;
+    mesh.draw(ctx, target, shader)?;
+    let direct = screenshot(ctx, target)?;
+
+    let target = ctx.create_render_target(RenderTarget::with_clear_color(
+        ctx.viewport(),
+        Color32::BLACK,
+    ))?;
+    let mut list = ctx.record();
+    list.clear(target).draw(target, shader, mesh);
+    check!(list.len() == 2);
+
+    //nothing happens until the list is submitted
+    ctx.render_target_mut(target)
+        .ok_or_else(|| anyhow::anyhow!("render target not found"))?
+        .set_clear_color(Some(Color32::RED));
+    ctx.submit(&list)?;
+    let img = screenshot(ctx, target)?;
+    check!(img.sample(0, 0) == Some(Pixel::RgbU8([255, 0, 0])));
+
+    ctx.render_target_mut(target)
+        .ok_or_else(|| anyhow::anyhow!("render target not found"))?
+        .set_clear_color(Some(Color32::BLACK));
+    ctx.submit(&list)?;
+    let img = screenshot(ctx, target)?;
+    check!(img.difference_region(&direct, 0.0)?.is_none());
+
+    //the clear binds its own target, limiting it to the viewport of that target
+    let viewport = ctx.viewport();
+    let left = URect::new(0, 0, viewport.width / 2, viewport.height);
+    let half = ctx.create_render_target(RenderTarget::with_clear_color(left, Color32::RED))?;
+    ctx.submit(ctx.record().clear(half))?;
+    let img = screenshot(ctx, target)?;
+    check!(img.sample(0, 0) == Some(Pixel::RgbU8([255, 0, 0])));
+    check!(img.sample(viewport.width - 1, 0) == Some(Pixel::RgbU8([0, 0, 0])));
+
+    Ok(())
+}
+
+fn fence(ctx: &mut impl Context) -> TestResult {
+    let target = black_screen(ctx)?;
+    let shader = create_shader(ctx, POINT_VS, WHITE_FS)?;
+    let layout = create_vec3_layout(ctx, &[0.0, 0.0, 4.0])?;
+
+    ctx.set_program_point_size(true);
+    ctx.draw(target, Primitive::Points, shader, layout, 0, 1)?;
+    let fence = ctx.insert_fence()?;
+
+    //one second is plenty for a single point
+    let status = ctx.wait_fence(fence, 1_000_000_000)?;
+    check!(status == fence::Status::Signaled);
+
+    //signaled fences stay signaled
+    let status = ctx.wait_fence(fence, 0)?;
+    check!(status == fence::Status::Signaled);
+
+    let img = screenshot(ctx, target)?;
+    check!(count_pixels(&img, WHITE, 0, img.width) == 16);
+
+    ctx.delete_fence(fence)?;
+    let deleted = matches!(ctx.wait_fence(fence, 0), Err(Error::ResourceNotFound));
+    check!(deleted);
+
+    Ok(())
+}
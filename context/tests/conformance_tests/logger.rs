@@ -0,0 +1,45 @@
+//! Logger that keeps the warnings and errors of the context, so tests can check what was reported
+use std::sync::Mutex;
+
+static WARNINGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Forwards the records to `env_logger`, and records the warnings and errors
+#[cfg(not(target_arch = "wasm32"))]
+struct Logger(env_logger::Logger);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl log::Log for Logger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Warn || self.0.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if record.level() <= log::Level::Warn {
+            if let Ok(mut warnings) = WARNINGS.lock() {
+                warnings.push(record.args().to_string());
+            }
+        }
+        self.0.log(record);
+    }
+
+    fn flush(&self) {
+        self.0.flush();
+    }
+}
+
+/// Installs the logger, warnings are recorded even when `env_logger` filters them out
+#[cfg(not(target_arch = "wasm32"))]
+pub fn init(logger: env_logger::Logger) -> Result<(), log::SetLoggerError> {
+    let level = logger.filter().max(log::LevelFilter::Warn);
+    log::set_boxed_logger(Box::new(Logger(logger)))?;
+    log::set_max_level(level);
+    Ok(())
+}
+
+/// Takes the warnings and errors that were logged since the last call
+pub fn take_warnings() -> Vec<String> {
+    WARNINGS
+        .lock()
+        .map(|mut warnings| std::mem::take(&mut *warnings))
+        .unwrap_or_default()
+}
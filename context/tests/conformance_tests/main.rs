@@ -25,11 +25,14 @@
 
 #[macro_use]
 mod runner;
+mod logger;
 mod platform;
 
 mod buffer;
 mod context;
+mod draw;
 mod render_target;
+mod shader;
 
 const CONTEXT_WIDTH: u32 = 800;
 const CONTEXT_HEIGHT: u32 = 600;
@@ -46,12 +49,17 @@ fn collect_tests() -> Vec<runner::TestCase> {
     tests.append(&mut context::tests());
     tests.append(&mut render_target::tests());
     tests.append(&mut buffer::tests());
+    tests.append(&mut draw::tests());
+    tests.append(&mut shader::tests());
 
     tests
 }
 
 fn collect_suits() -> Vec<TestSuite> {
-    vec![TestSuite::GlfwWithOpenGL(4, 3), TestSuite::WebGL]
+    platform::factories()
+        .into_iter()
+        .map(TestSuite::new)
+        .collect()
 }
 
 cfg_if::cfg_if! {
@@ -65,11 +73,12 @@ cfg_if::cfg_if! {
         }
    } else {
         fn main() -> Result<(), &'static str> {
-            env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
+            let logger = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
                 .format_level(false)
                 .format_target(false)
                 .format_timestamp(None)
-                .init();
+                .build();
+            logger::init(logger).expect("failed to init logger");
 
             shared_main()
         }
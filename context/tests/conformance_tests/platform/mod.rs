@@ -14,7 +14,7 @@ cfg_if::cfg_if! {
 
 pub enum Context {
     #[cfg(not(target_arch = "wasm32"))]
-    OpenGLGLFW(cac_context::opengl::Context<opengl::GLFWContext>),
+    OpenGL(cac_context::opengl::Context<NativeContext>),
     #[cfg(target_arch = "wasm32")]
     WebGL(WebGLContext),
 }
@@ -23,24 +23,85 @@ impl Context {
     pub fn reset(&mut self) {
         match self {
             #[cfg(not(target_arch = "wasm32"))]
-            Self::OpenGLGLFW(ctx) => ctx.reset(),
+            Self::OpenGL(ctx) => ctx.reset(),
         }
     }
 
     pub fn poll_errors(&mut self) -> Option<Vec<String>> {
         match self {
             #[cfg(not(target_arch = "wasm32"))]
-            Self::OpenGLGLFW(ctx) => ctx.poll_errors(),
+            Self::OpenGL(ctx) => ctx.poll_errors(),
         }
     }
 }
 
-pub enum TestSuite {
-    GlfwWithOpenGL(u8, u8),
-    WebGL,
+/// Window or surface of any backend that provides an OpenGL context, like GLFW or headless EGL
+#[cfg(not(target_arch = "wasm32"))]
+pub struct NativeContext(pub Box<dyn cac_context::opengl::GLContext>);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl cac_context::opengl::GLContext for NativeContext {
+    fn swap_buffers(&mut self) {
+        self.0.swap_buffers();
+    }
+
+    fn get_proc_address(&mut self, name: &'static str) -> *const std::ffi::c_void {
+        self.0.get_proc_address(name)
+    }
+}
+
+/// Creates the context that a suite runs the tests on.
+///
+/// Backends are added by implementing the trait and registering the factory in `factories`.
+pub trait TestContextFactory {
+    /// Name of the suite in the log
+    fn name(&self) -> String;
+    /// Context of the suite in the report
+    fn context(&self) -> &'static str;
+
+    /// # Errors
+    /// When the context can't be created on this machine, which fails the suite.
+    fn create(&self) -> anyhow::Result<Context>;
+}
+
+/// Factories of all contexts supported on the platform
+pub fn factories() -> Vec<Box<dyn TestContextFactory>> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_arch = "wasm32")] {
+            vec![Box::new(WebGLFactory)]
+        } else {
+            vec![Box::new(opengl::GlfwFactory { version: (4, 3) })]
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+struct WebGLFactory;
+
+#[cfg(target_arch = "wasm32")]
+impl TestContextFactory for WebGLFactory {
+    fn name(&self) -> String {
+        "WebGL".to_string()
+    }
+
+    fn context(&self) -> &'static str {
+        "webgl"
+    }
+
+    fn create(&self) -> anyhow::Result<Context> {
+        Ok(Context::WebGL(WebGLContext {}))
+    }
+}
+
+pub struct TestSuite {
+    factory: Box<dyn TestContextFactory>,
 }
 
 impl TestSuite {
+    pub fn new(factory: Box<dyn TestContextFactory>) -> Self {
+        Self { factory }
+    }
+
     pub fn run(&self, tests: &[runner::TestCase]) -> runner::TestReport {
         let old_hook = std::panic::take_hook();
         let panic_loc = Arc::new(Mutex::new(None));
@@ -56,32 +117,14 @@ impl TestSuite {
             })
         });
 
-        log::info!("\n------ Context: {} ------", &self.name());
-        let report = match *self {
-            #[cfg(not(target_arch = "wasm32"))]
-            Self::GlfwWithOpenGL(major, minor) => match opengl::new_glfw((major, minor)) {
-                Ok(mut ctx) => run_tests(&panic_loc, "glfw_with_opengl", &mut ctx, tests),
-                Err(e) => runner::TestReport::with_entry("glfw_with_opengl", e.to_string()),
-            },
-            #[cfg(target_arch = "wasm32")]
-            Self::WebGL => {
-                let mut ctx = Context::WebGL(WebGLContext {});
-                run_tests(&panic_loc, "webgl", &mut ctx, tests)
-            }
-            _ => {
-                log::info!("Context not supported on platform, skipping");
-                runner::TestReport::new()
-            }
+        log::info!("\n------ Context: {} ------", self.factory.name());
+        let context = self.factory.context();
+        let report = match self.factory.create() {
+            Ok(mut ctx) => run_tests(&panic_loc, context, &mut ctx, tests),
+            Err(e) => runner::TestReport::with_entry(context, e.to_string()),
         };
 
         std::panic::set_hook(old_hook);
         report
     }
-
-    fn name(&self) -> String {
-        match self {
-            Self::GlfwWithOpenGL(major, minor) => format!("GLFW OpenGL{major}.{minor}"),
-            Self::WebGL => "WebGL".to_string(),
-        }
-    }
 }
@@ -1,6 +1,8 @@
 use cac_context::{opengl, Context};
 use glfw::Context as _;
 
+use super::{NativeContext, TestContextFactory};
+
 pub struct GLFWContext(glfw::Window);
 
 impl opengl::GLContext for GLFWContext {
@@ -13,6 +15,26 @@ impl opengl::GLContext for GLFWContext {
     }
 }
 
+/// Core profile context in a visible GLFW window
+pub struct GlfwFactory {
+    pub version: (u8, u8),
+}
+
+impl TestContextFactory for GlfwFactory {
+    fn name(&self) -> String {
+        let (major, minor) = self.version;
+        format!("GLFW OpenGL{major}.{minor}")
+    }
+
+    fn context(&self) -> &'static str {
+        "glfw_with_opengl"
+    }
+
+    fn create(&self) -> anyhow::Result<super::Context> {
+        new_glfw(self.version)
+    }
+}
+
 pub fn new_glfw(version: (u8, u8)) -> anyhow::Result<super::Context, anyhow::Error> {
     let mut glfw = glfw::init(glfw::FAIL_ON_ERRORS)?;
 
@@ -40,12 +62,12 @@ pub fn new_glfw(version: (u8, u8)) -> anyhow::Result<super::Context, anyhow::Err
     // Make the window's context current
     window.make_current();
 
-    let mut ctx = opengl::Context::new(GLFWContext(window))?;
+    let mut ctx = opengl::Context::new(NativeContext(Box::new(GLFWContext(window))))?;
     ctx.update();
 
     glfw.poll_events();
 
-    let ctx = super::Context::OpenGLGLFW(ctx);
+    let ctx = super::Context::OpenGL(ctx);
 
     Ok(ctx)
 }
@@ -1,11 +1,11 @@
-use cac_context::{render_target::Native, Context, RenderTarget};
+use cac_context::{render_target::Native, Context, Error, RenderTarget};
 use cac_core::{
-    image::{self, Format, Image},
+    image::{self, Format, Image, Pixel},
     math::URect,
     Color32,
 };
 
-use crate::{runner::TestCase, TestResult};
+use crate::{draw::screenshot, runner::TestCase, TestResult};
 
 const FOUR_COLOR_VP: &[u8] = include_bytes!("res/screen_viewport.png");
 
@@ -19,7 +19,15 @@ fn sample(img1: &Image, img2: &Image, x: u32, y: u32) -> bool {
 }
 
 pub fn tests() -> Vec<TestCase> {
-    vec![TEST!(clear_color), TEST!(screen_viewport)]
+    vec![
+        TEST!(clear_color),
+        TEST!(screen_viewport),
+        TEST!(clear_rect),
+        TEST!(read_pixels_async),
+        TEST!(read_pixels_red),
+        TEST!(screen_target),
+        TEST!(scissor_test),
+    ]
 }
 
 fn clear_color(ctx: &mut impl Context) -> TestResult {
@@ -121,3 +129,222 @@ fn screen_viewport(ctx: &mut impl Context) -> TestResult {
 
     Ok(())
 }
+
+fn clear_rect(ctx: &mut impl Context) -> TestResult {
+    let dirty = URect::new(100, 50, 20, 10);
+
+    let handle = ctx.create_render_target(RenderTarget::with_clear_color(
+        ctx.viewport(),
+        Color32::BLUE,
+    ))?;
+
+    if let Some(rt) = ctx.render_target_mut(handle) {
+        rt.clear();
+        rt.set_clear_color(Some(Color32::RED));
+        rt.clear_rect(dirty)?;
+    } else {
+        error!("render target not found")
+    }
+
+    if let Some(rt) = ctx.render_target(handle) {
+        let screenshot = rt.read_pixels(Format::RgbU8, ctx.viewport())?;
+        let blue = Image::with_color32(
+            screenshot.width,
+            screenshot.height,
+            Color32::BLUE,
+            Format::RgbU8,
+        )?;
+
+        //only the dirty rect differs from the blue background
+        check!(screenshot.difference_region(&blue, 0.01)? == Some(dirty));
+    } else {
+        error!("render target not found")
+    }
+
+    //the scissor of a pushed viewport is restored, so the next clear stays inside of it
+    let viewport = ctx.viewport();
+    let quarter = URect::new(0, 0, viewport.width / 2, viewport.height / 2);
+    ctx.push_viewport(quarter)?;
+    if let Some(rt) = ctx.render_target_mut(handle) {
+        rt.set_clear_color(Some(Color32::BLUE));
+        rt.clear();
+        rt.set_clear_color(Some(Color32::RED));
+        rt.clear_rect(URect::new(0, 0, 10, 10))?;
+        rt.set_clear_color(Some(Color32::GREEN));
+        rt.clear();
+    } else {
+        error!("render target not found")
+    }
+    ctx.pop_viewport();
+
+    if let Some(rt) = ctx.render_target(handle) {
+        let screenshot = rt.read_pixels(Format::RgbU8, viewport)?;
+        let blue = Image::with_color32(
+            screenshot.width,
+            screenshot.height,
+            Color32::BLUE,
+            Format::RgbU8,
+        )?;
+        check!(screenshot.difference_region(&blue, 0.01)? == Some(quarter));
+    } else {
+        error!("render target not found")
+    }
+
+    Ok(())
+}
+
+fn read_pixels_async(ctx: &mut impl Context) -> TestResult {
+    let handle = ctx.create_render_target(RenderTarget::with_clear_color(
+        ctx.viewport(),
+        Color32::UNITY_YELLOW,
+    ))?;
+
+    let dirty = URect::new(10, 20, 30, 40);
+    if let Some(rt) = ctx.render_target_mut(handle) {
+        rt.clear();
+        rt.set_clear_color(Some(Color32::PERSIAN_INDIGO));
+        rt.clear_rect(dirty)?;
+    } else {
+        error!("render target not found")
+    }
+
+    let read = ctx.read_pixels_async(handle, Format::RgbU8, ctx.viewport())?;
+    let expected = match ctx.render_target(handle) {
+        Some(rt) => rt.read_pixels(Format::RgbU8, ctx.viewport())?,
+        None => error!("render target not found"),
+    };
+
+    //the next frame overwrites the screen, the pending read keeps the old content
+    if let Some(rt) = ctx.render_target_mut(handle) {
+        rt.set_clear_color(Some(Color32::BLACK));
+        rt.clear();
+    }
+    ctx.update();
+
+    let mut result = None;
+    for _ in 0..1000 {
+        result = ctx.pixel_read_result(read)?;
+        if result.is_some() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+
+    if let Some(img) = result {
+        check!(img.difference_region(&expected, 0.0)?.is_none());
+    } else {
+        error!("pixel read didn't finish")
+    }
+
+    //the read is consumed
+    check!(ctx.pixel_read_result(read).is_err());
+
+    //the number of pixels overflows u32
+    let huge = URect::new(0, 0, 0x1_0000, 0x1_0000);
+    let overflow = matches!(
+        ctx.read_pixels_async(handle, Format::RgbU8, huge),
+        Err(Error::ConversionFailed(_))
+    );
+    check!(overflow);
+
+    Ok(())
+}
+
+fn read_pixels_red(ctx: &mut impl Context) -> TestResult {
+    let handle = ctx.create_render_target(RenderTarget::with_clear_color(
+        ctx.viewport(),
+        Color32::from_rgb(0.6, 0.2, 0.4),
+    ))?;
+    let rt = ctx
+        .render_target_mut(handle)
+        .ok_or_else(|| anyhow::anyhow!("render target not found"))?;
+    rt.clear();
+
+    //odd width, so that the rows aren't aligned to 4 bytes
+    let rect = URect::new(5, 5, 13, 7);
+
+    let img = rt.read_pixels(Format::RedU8, rect)?;
+    let all_red = (0..rect.height)
+        .flat_map(|y| (0..rect.width).map(move |x| (x, y)))
+        .all(|(x, y)| img.sample(x, y) == Some(Pixel::RedU8(153)));
+    check!(all_red);
+
+    let img = rt.read_pixels(Format::RedF32, rect)?;
+    let corner = img.sample(rect.width - 1, rect.height - 1);
+    check!(matches!(corner, Some(Pixel::RedF32(red)) if (red - 0.6).abs() < 0.01));
+
+    Ok(())
+}
+
+fn screen_target(ctx: &mut impl Context) -> TestResult {
+    let screen = ctx.screen_target();
+    let created = ctx.create_render_target(RenderTarget::with_clear_color(
+        ctx.viewport(),
+        Color32::BLUE,
+    ))?;
+    check!(ctx.render_target(screen).is_some());
+    ctx.clear(created)?;
+
+    ctx.reset();
+
+    //only the created render target is gone
+    check!(ctx.screen_target() == screen);
+    check!(ctx.render_target(screen).is_some());
+    check!(ctx.render_target(created).is_none());
+
+    //the screen target can't be changed and has no clear color, clearing keeps the pixels
+    check!(ctx.render_target_mut(screen).is_none());
+    ctx.clear(screen)?;
+
+    let screenshot = ctx
+        .render_target(screen)
+        .ok_or_else(|| anyhow::anyhow!("screen target not found"))?
+        .read_pixels(Format::RgbU8, ctx.viewport())?;
+    check!(screenshot.sample(0, 0) == Some(Pixel::RgbU8([0, 0, 255])));
+
+    Ok(())
+}
+
+fn scissor_test(ctx: &mut impl Context) -> TestResult {
+    let corner = URect::new(0, 0, 10, 10);
+    let background = ctx.create_render_target(RenderTarget::with_clear_color(
+        ctx.viewport(),
+        Color32::BLACK,
+    ))?;
+    let small = ctx.create_render_target(RenderTarget::with_clear_color(corner, Color32::RED))?;
+
+    let viewport = ctx.viewport();
+    let red = Image::with_color32(viewport.width, viewport.height, Color32::RED, Format::RgbU8)?;
+    let black = Image::with_color32(
+        viewport.width,
+        viewport.height,
+        Color32::BLACK,
+        Format::RgbU8,
+    )?;
+
+    //without scissoring, the clear of the small target covers everything
+    ctx.set_scissor_test(false);
+    ctx.clear_color_only(background)?;
+    ctx.clear_color_only(small)?;
+    let img = screenshot(ctx, background)?;
+    check!(img.difference_region(&red, 0.01)?.is_none());
+
+    //clear_rect is still limited to the rect
+    if let Some(rt) = ctx.render_target_mut(background) {
+        rt.clear_rect(URect::new(20, 20, 5, 5))?;
+    } else {
+        error!("render target not found")
+    }
+    let img = screenshot(ctx, background)?;
+    let cleared = img.difference_region(&red, 0.01)?;
+    check!(cleared.map(|r| (r.width, r.height)) == Some((5, 5)));
+
+    ctx.set_scissor_test(true);
+    ctx.clear_color_only(background)?;
+    ctx.clear_color_only(small)?;
+    let img = screenshot(ctx, background)?;
+    let cleared = img.difference_region(&black, 0.01)?;
+    check!(cleared.map(|r| (r.width, r.height)) == Some((10, 10)));
+
+    Ok(())
+}
@@ -2,40 +2,68 @@ use crate::Context;
 use std::{
     panic::{catch_unwind, AssertUnwindSafe},
     sync::{Arc, Mutex},
-    //time::Instant,
+    time::Duration,
 };
 
 pub const TEST_OK: &str = concat!("\x1b[92m", "ok", "\x1b[0m");
 pub const TEST_FAIL: &str = concat!("\x1b[31m", "FAILED", "\x1b[0m");
 
+/// Tests taking longer are listed after the suite
+pub const SLOW_TEST: Duration = Duration::from_millis(500);
+
 pub struct Entry {
     pub name: &'static str,
     pub reason: String,
+    pub duration: Duration,
 }
 
 pub struct TestReport {
     pub errors: Vec<Entry>,
+    pub durations: Vec<(&'static str, Duration)>,
+    pub duration: Duration,
     pub context: &'static str,
 }
 
 impl TestReport {
-    pub const fn new() -> Self {
-        Self {
-            errors: Vec::new(),
-            context: "",
-        }
-    }
     pub const fn with_context(context: &'static str) -> Self {
         Self {
             errors: Vec::new(),
+            durations: Vec::new(),
+            duration: Duration::ZERO,
             context,
         }
     }
 
     pub fn with_entry(name: &'static str, reason: String) -> Self {
         Self {
-            errors: vec![Entry { name, reason }],
-            context: name,
+            errors: vec![Entry {
+                name,
+                reason,
+                duration: Duration::ZERO,
+            }],
+            ..Self::with_context(name)
+        }
+    }
+
+    /// Tests that took longer than `SLOW_TEST`, the slowest first
+    pub fn slow_tests(&self) -> Vec<(&'static str, Duration)> {
+        let mut slow: Vec<_> = self
+            .durations
+            .iter()
+            .copied()
+            .filter(|(_, duration)| *duration > SLOW_TEST)
+            .collect();
+        slow.sort_by_key(|(_, duration)| std::cmp::Reverse(*duration));
+        slow
+    }
+
+    pub fn print_slow_tests(&self) {
+        let slow = self.slow_tests();
+        if !slow.is_empty() {
+            log::warn!("slow tests:");
+            for (name, duration) in slow {
+                log::warn!("    {name} took {:.2}s", duration.as_secs_f32());
+            }
         }
     }
 
@@ -47,7 +75,12 @@ impl TestReport {
         if !self.errors.is_empty() {
             log::error!("failure:");
             for e in &self.errors {
-                log::error!("\n---- {} stdout ----\n{}", e.name, e.reason);
+                log::error!(
+                    "\n---- {} stdout ({:.2}s) ----\n{}",
+                    e.name,
+                    e.duration.as_secs_f32(),
+                    e.reason
+                );
             }
         }
     }
@@ -61,7 +94,7 @@ macro_rules! TEST {
             func: |ctx: &mut $crate::Context| -> _ {
                 match ctx {
                     #[cfg(not(target_arch = "wasm32"))]
-                    $crate::Context::OpenGLGLFW(ctx) => $f(ctx),
+                    $crate::Context::OpenGL(ctx) => $f(ctx),
                     #[cfg(target_arch = "wasm32")]
                     $crate::Context::WebGL(ctx) => $f(ctx),
                 }
@@ -86,6 +119,32 @@ macro_rules! error {
     };
 }
 
+/// Measures the time since it was started.
+///
+/// `Instant` panics on wasm without access to the browser clock, so durations are always zero
+/// there.
+struct Timer {
+    #[cfg(not(target_arch = "wasm32"))]
+    start: std::time::Instant,
+}
+
+impl Timer {
+    fn start() -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            start: std::time::Instant::now(),
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        #[cfg(not(target_arch = "wasm32"))]
+        return self.start.elapsed();
+
+        #[cfg(target_arch = "wasm32")]
+        Duration::ZERO
+    }
+}
+
 pub struct TestCase {
     pub name: &'static str,
     pub func: fn(&mut Context) -> anyhow::Result<(), anyhow::Error>,
@@ -113,22 +172,27 @@ pub fn run_tests(
 ) -> TestReport {
     let mut report = TestReport::with_context(prefix);
     log::info!("\nrunning {} tests", tests.len());
-    //let timer = Instant::now();
+    let suite_timer = Timer::start();
     for test in tests.iter() {
         //always reset the context to prevent state leaking through
         ctx.reset();
         print!("test {} ... ", test.name);
+        let timer = Timer::start();
         let result = catch_unwind(AssertUnwindSafe(|| (test.func)(ctx)));
+        let duration = timer.elapsed();
+        report.durations.push((test.name, duration));
 
         let error_entry = match result {
             Ok(result) => match result {
                 Ok(()) => ctx.poll_errors().map(|e| Entry {
                     name: test.name,
                     reason: e.concat(),
+                    duration,
                 }),
                 Err(e) => Some(Entry {
                     name: test.name,
                     reason: e.to_string(),
+                    duration,
                 }),
             },
             Err(e) => {
@@ -138,6 +202,7 @@ pub fn run_tests(
                 Some(Entry {
                     name: test.name,
                     reason: msg,
+                    duration,
                 })
             }
         };
@@ -153,10 +218,11 @@ pub fn run_tests(
     let failed = report.errors.len();
     let test_result = if failed == 0 { TEST_OK } else { TEST_FAIL };
     let passed = tests.len() - failed;
-    //let time = timer.elapsed().as_secs_f32();
-    let time = 0.0;
+    report.duration = suite_timer.elapsed();
+    let time = report.duration.as_secs_f32();
 
     log::info!("\ntest result: {test_result}. {passed} passed; {failed} failed; 0 ignored; 0 measured; 0 filtered out; finished in {time:.2}s\n");
+    report.print_slow_tests();
 
     report
 }
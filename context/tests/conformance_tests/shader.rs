@@ -0,0 +1,175 @@
+use cac_context::{
+    buffer::{Access, Kind, Usage},
+    Buffer, Context, Error, Primitive,
+};
+
+use crate::{
+    draw::{
+        black_screen, count_pixels, create_shader, create_vec3_layout, screenshot, POINT_VS, WHITE,
+        WHITE_FS,
+    },
+    runner::TestCase,
+    TestResult,
+};
+
+const COLORED_VS: &str = r"
+#version 430 core
+layout(location = 0) in vec3 pos;
+layout(location = 1) in vec4 color;
+
+uniform mat4 transform;
+
+out vec4 vertex_color;
+
+void main() {
+    gl_Position = transform * vec4(pos, 1.0);
+    vertex_color = color;
+}
+";
+
+const COLORED_FS: &str = r"
+#version 430 core
+in vec4 vertex_color;
+out vec4 color;
+
+void main() {
+    color = vertex_color;
+}
+";
+
+pub fn tests() -> Vec<TestCase> {
+    vec![
+        TEST!(reflection),
+        TEST!(binary),
+        TEST!(early_z),
+        TEST!(uniform_block_binding),
+    ]
+}
+
+fn reflection(ctx: &mut impl Context) -> TestResult {
+    let shader = create_shader(ctx, COLORED_VS, COLORED_FS)?;
+    let reflection = ctx.shader_reflection(shader)?;
+
+    check!(reflection.attributes.len() == 2);
+    check!(reflection.attribute("pos").and_then(|a| a.location) == Some(0));
+    check!(reflection.attribute("color").and_then(|a| a.location) == Some(1));
+
+    if let Some(transform) = reflection.uniform("transform") {
+        check!(transform.location.is_some());
+        check!(transform.size == 1);
+    } else {
+        error!("transform uniform not found");
+    }
+
+    Ok(())
+}
+
+fn binary(ctx: &mut impl Context) -> TestResult {
+    let shader = create_shader(ctx, POINT_VS, WHITE_FS)?;
+
+    let (format, binary) = match ctx.get_shader_binary(shader) {
+        Ok(binary) => binary,
+        //drivers are allowed to support no binary formats at all
+        Err(Error::InvalidContext(_)) => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    check!(!binary.is_empty());
+
+    let cached = ctx.create_shader_from_binary(format, &binary)?;
+    let layout = create_vec3_layout(ctx, &[0.0, 0.0, 4.0])?;
+    ctx.set_program_point_size(true);
+
+    let target = black_screen(ctx)?;
+    ctx.draw(target, Primitive::Points, cached, layout, 0, 1)?;
+    let img = screenshot(ctx, target)?;
+    check!(count_pixels(&img, WHITE, 0, img.width) == 16);
+
+    let rejected = ctx.create_shader_from_binary(format, &binary[..binary.len() / 2]);
+    check!(matches!(rejected, Err(Error::FailedToLinkShader(_))));
+
+    //a rejected binary is expected to report an error in the debug output
+    ctx.poll_errors();
+
+    Ok(())
+}
+
+fn early_z(ctx: &mut impl Context) -> TestResult {
+    const EARLY_Z_FS: &str = r"
+    #version 430 core
+    layout(early_fragment_tests) in;
+    out vec4 color;
+
+    void main() {
+        color = vec4(1.0);
+    }
+    ";
+
+    let early = create_shader(ctx, POINT_VS, EARLY_Z_FS)?;
+    let late = create_shader(ctx, POINT_VS, WHITE_FS)?;
+
+    let (early, late) = (
+        ctx.shader_uses_early_z(early)?,
+        ctx.shader_uses_early_z(late)?,
+    );
+    check!(early);
+    check!(!late);
+
+    Ok(())
+}
+
+fn uniform_block_binding(ctx: &mut impl Context) -> TestResult {
+    const CAMERA_VS: &str = r"
+    #version 430 core
+    layout(location = 0) in vec3 pos;
+    uniform Camera {
+        vec4 offset;
+    };
+
+    void main() {
+        gl_Position = vec4(pos.xy + SIGN * offset.xy, 0.0, 1.0);
+        gl_PointSize = pos.z;
+    }
+    ";
+
+    //the same block, moving the points to opposite sides
+    let left_vs = CAMERA_VS.replace("SIGN", "1.0");
+    let right_vs = CAMERA_VS.replace("SIGN", "-1.0");
+    let left = create_shader(ctx, &left_vs, WHITE_FS)?;
+    let right = create_shader(ctx, &right_vs, WHITE_FS)?;
+
+    ctx.set_uniform_block_binding(left, "Camera", 3)?;
+    ctx.set_uniform_block_binding(right, "Camera", 3)?;
+
+    let camera: [f32; 4] = [-0.5, 0.0, 0.0, 0.0];
+    let buffer = ctx.create_buffer(&Buffer::with_uniform_data(
+        Access::Once,
+        Usage::Write,
+        &camera,
+    ))?;
+    ctx.bind_buffer_range(buffer, 3, 0, 16, Kind::Uniform)?;
+
+    let target = black_screen(ctx)?;
+    let layout = create_vec3_layout(ctx, &[0.0, 0.0, 4.0])?;
+    ctx.set_program_point_size(true);
+    ctx.draw(target, Primitive::Points, left, layout, 0, 1)?;
+    ctx.draw(target, Primitive::Points, right, layout, 0, 1)?;
+
+    let img = screenshot(ctx, target)?;
+    let half = img.width / 2;
+    check!(count_pixels(&img, WHITE, 0, half) == 16);
+    check!(count_pixels(&img, WHITE, half, img.width) == 16);
+
+    let missing = matches!(
+        ctx.set_uniform_block_binding(left, "Lights", 3),
+        Err(Error::InvalidOperation(_))
+    );
+    check!(missing);
+
+    let out_of_range = matches!(
+        ctx.set_uniform_block_binding(left, "Camera", u32::MAX),
+        Err(Error::InvalidOperation(_))
+    );
+    check!(out_of_range);
+
+    Ok(())
+}
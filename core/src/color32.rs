@@ -1,3 +1,32 @@
+use crate::float;
+use glam::{Mat3, Vec3};
+
+/// Kinds of dichromacy, color blindness in which one of the three cone types is missing
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum ColorBlindness {
+    /// No long wavelength cones, red appears dark
+    Protanopia,
+    /// No medium wavelength cones, red and green are confused
+    Deuteranopia,
+    /// No short wavelength cones, blue and yellow are confused
+    Tritanopia,
+}
+
+/// Linear rgb into LMS cone responses, from "Digital video colourmaps for checking the legibility
+/// of displays by dichromats" by Viénot, Brettel and Mollon. The matrices are column major.
+const RGB_TO_LMS: Mat3 = Mat3::from_cols(
+    Vec3::new(17.8824, 3.455_65, 0.029_956_6),
+    Vec3::new(43.5161, 27.1554, 0.184_309),
+    Vec3::new(4.119_35, 3.867_14, 1.467_09),
+);
+
+/// Inverse of `RGB_TO_LMS`
+const LMS_TO_RGB: Mat3 = Mat3::from_cols(
+    Vec3::new(0.080_944_45, -0.010_248_534, -0.000_365_296_94),
+    Vec3::new(-0.130_504_41, 0.054_019_33, -0.004_121_614_7),
+    Vec3::new(0.116_721_07, -0.113_614_71, 0.693_511_4),
+);
+
 #[derive(Debug, PartialEq, Copy, Clone)]
 #[repr(C)]
 pub struct Color32 {
@@ -50,6 +79,148 @@ impl Color32 {
         [self.r, self.g, self.b, self.a]
     }
 
+    /// Packs the color into a single u32, with 8 bits per channel.
+    ///
+    /// The channels are clamped to 0.0 - 1.0 and stored as `0xRRGGBBAA`, with red in the most
+    /// significant byte. Written to memory as a big endian value(`to_be_bytes`), the bytes are in
+    /// RGBA order, matching a normalized u8 vec4 vertex attribute.
+    #[must_use]
+    pub fn to_u32(&self) -> u32 {
+        u32::from_be_bytes(self.as_rgba_u8())
+    }
+
+    /// Unpacks a color from a u32 in the `0xRRGGBBAA` layout, see `to_u32`.
+    #[must_use]
+    pub fn from_u32(packed: u32) -> Self {
+        let [r, g, b, a] = packed.to_be_bytes().map(|c| f32::from(c) / 255.0);
+        Self { r, g, b, a }
+    }
+
+    /// Reinterprets the colors as a flat slice of their rgba channels, for example to upload them
+    /// as vertex data.
+    #[must_use]
+    pub const fn slice_as_f32(colors: &[Self]) -> &[f32] {
+        //SAFETY: Color32 is repr(C) and consists of exactly four f32s, without any padding.
+        unsafe { core::slice::from_raw_parts(colors.as_ptr().cast(), colors.len() * 4) }
+    }
+
+    /// Reinterprets a flat slice of rgba channels as colors, the inverse of `slice_as_f32`.
+    ///
+    /// Returns `None` when the length isn't a multiple of four.
+    #[must_use]
+    pub const fn from_f32_slice(channels: &[f32]) -> Option<&[Self]> {
+        if !channels.len().is_multiple_of(4) {
+            return None;
+        }
+
+        //SAFETY: Color32 is repr(C) and consists of exactly four f32s, so it has the same
+        //alignment as f32 and every 4 f32s form a valid color.
+        Some(unsafe { core::slice::from_raw_parts(channels.as_ptr().cast(), channels.len() / 4) })
+    }
+
+    /// Linearly interpolates between the colors, including alpha.
+    ///
+    /// `t` of 0.0 returns `self`, 1.0 returns `other`. Values outside of 0.0 - 1.0 extrapolate.
+    #[must_use]
+    pub fn lerp(&self, other: Self, t: f32) -> Self {
+        let lerp = |a: f32, b: f32| float::mul_add(b - a, t, a);
+        Self {
+            r: lerp(self.r, other.r),
+            g: lerp(self.g, other.g),
+            b: lerp(self.b, other.b),
+            a: lerp(self.a, other.a),
+        }
+    }
+
+    /// Mixes the colors like paint, in the red-yellow-blue color space, including alpha.
+    ///
+    /// Unlike `lerp`, red and blue give purple and red and yellow give orange.
+    /// `t` of 0.0 returns `self`, 1.0 returns `other`.
+    #[must_use]
+    pub fn mix_subtractive(&self, other: &Self, t: f32) -> Self {
+        let lerp = |a: f32, b: f32| float::mul_add(b - a, t, a);
+
+        let [r0, y0, b0] = rgb_to_ryb(self.as_rgb());
+        let [r1, y1, b1] = rgb_to_ryb(other.as_rgb());
+        let [r, g, b] = ryb_to_rgb([lerp(r0, r1), lerp(y0, y1), lerp(b0, b1)]);
+
+        Self::from_rgba(r, g, b, lerp(self.a, other.a))
+    }
+
+    /// Squared euclidean distance of the linear rgb channels, alpha is ignored.
+    ///
+    /// Cheaper than the distance itself and orders colors the same way, for example when matching
+    /// them against a palette.
+    #[must_use]
+    pub fn distance_squared(&self, other: &Self) -> f32 {
+        let (r, g, b) = (self.r - other.r, self.g - other.g, self.b - other.b);
+        float::mul_add(r, r, float::mul_add(g, g, b * b))
+    }
+
+    /// Finds the palette color closest to this one, by `distance_squared`.
+    ///
+    /// Returns the first of equally close colors, or `None` when the palette is empty.
+    #[must_use]
+    pub fn nearest<'a>(&self, palette: &'a [Self]) -> Option<&'a Self> {
+        palette.iter().min_by(|a, b| {
+            self.distance_squared(a)
+                .total_cmp(&self.distance_squared(b))
+        })
+    }
+
+    /// Quantizes the linear channels to bytes, clamped to 0.0 - 1.0.
+    ///
+    /// Use these for linear textures, like `GL_RGBA8`. Colors are stored linear, so for textures
+    /// that the GPU decodes from sRGB, like `GL_SRGB8_ALPHA8`, use `as_rgba_u8_srgb` instead.
+    #[must_use]
+    pub fn as_rgba_u8(&self) -> [u8; 4] {
+        self.as_rgba().map(quantize)
+    }
+
+    /// Gamma encodes the rgb channels and quantizes them to bytes, clamped to 0.0 - 1.0.
+    /// Alpha stays linear, as sRGB textures don't encode it.
+    ///
+    /// Use these for sRGB textures, like `GL_SRGB8_ALPHA8`, see `as_rgba_u8` for linear ones.
+    #[must_use]
+    pub fn as_rgba_u8_srgb(&self) -> [u8; 4] {
+        let [r, g, b] = self.as_srgb();
+        [r, g, b, self.a].map(quantize)
+    }
+
+    /// Approximates how the color appears with the kind of color blindness, for example to check
+    /// the contrast of a palette. Alpha is kept and the channels are clamped to 0.0 - 1.0.
+    ///
+    /// The missing cone response is replaced by one estimated from the other two, so white,
+    /// black and grays stay unchanged.
+    #[must_use]
+    pub fn simulate_color_blindness(&self, kind: ColorBlindness) -> Self {
+        //in LMS space, the missing cone response is a mix of the other two
+        let simulation = match kind {
+            ColorBlindness::Protanopia => Mat3::from_cols(
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(2.023_44, 1.0, 0.0),
+                Vec3::new(-2.525_81, 0.0, 1.0),
+            ),
+            ColorBlindness::Deuteranopia => Mat3::from_cols(
+                Vec3::new(1.0, 0.494_207, 0.0),
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(0.0, 1.248_27, 1.0),
+            ),
+            ColorBlindness::Tritanopia => Mat3::from_cols(
+                Vec3::new(1.0, 0.0, -0.395_913),
+                Vec3::new(0.0, 1.0, 0.801_109),
+                Vec3::new(0.0, 0.0, 0.0),
+            ),
+        };
+
+        let lms = RGB_TO_LMS * Vec3::from_array(self.as_rgb());
+        let [r, g, b] = (LMS_TO_RGB * (simulation * lms))
+            .clamp(Vec3::ZERO, Vec3::ONE)
+            .to_array();
+
+        Self::from_rgba(r, g, b, self.a)
+    }
+
     #[must_use]
     pub fn as_srgb(&self) -> [f32; 3] {
         [self.r.to_gamma(), self.g.to_gamma(), self.b.to_gamma()]
@@ -62,6 +233,77 @@ impl Color32 {
     }
 }
 
+/// Maps a normalized channel onto a byte
+#[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+fn quantize(channel: f32) -> u8 {
+    float::round(channel.clamp(0.0, 1.0) * 255.0) as u8
+}
+
+/// Converts rgb into red-yellow-blue, following "Paint Inspired Color Mixing and Compositing
+/// for Visualization" by Gossett and Chen, with the hue preserving normalization.
+fn rgb_to_ryb([r, g, b]: [f32; 3]) -> [f32; 3] {
+    //remove the white
+    let white = r.min(g).min(b);
+    let (mut r, mut g, mut b) = (r - white, g - white, b - white);
+    let max_green = r.max(g).max(b);
+
+    //get the yellow out of red and green
+    let mut y = r.min(g);
+    r -= y;
+    g -= y;
+
+    //blue and green both contain blue, so halve them
+    if b > 0.0 && g > 0.0 {
+        b /= 2.0;
+        g /= 2.0;
+    }
+
+    //the remaining green is yellow and blue
+    y += g;
+    b += g;
+
+    let max_yellow = r.max(y).max(b);
+    if max_yellow > 0.0 {
+        let scale = max_green / max_yellow;
+        r *= scale;
+        y *= scale;
+        b *= scale;
+    }
+
+    [r + white, y + white, b + white]
+}
+
+/// Inverse of `rgb_to_ryb`
+fn ryb_to_rgb([r, y, b]: [f32; 3]) -> [f32; 3] {
+    let white = r.min(y).min(b);
+    let (mut r, mut y, mut b) = (r - white, y - white, b - white);
+    let max_yellow = r.max(y).max(b);
+
+    //get the green out of yellow and blue
+    let mut g = y.min(b);
+    y -= g;
+    b -= g;
+
+    if b > 0.0 && g > 0.0 {
+        b *= 2.0;
+        g *= 2.0;
+    }
+
+    //the remaining yellow is red and green
+    r += y;
+    g += y;
+
+    let max_green = r.max(g).max(b);
+    if max_green > 0.0 {
+        let scale = max_yellow / max_green;
+        r *= scale;
+        g *= scale;
+        b *= scale;
+    }
+
+    [r + white, g + white, b + white]
+}
+
 trait ToGamma {
     type Output;
     fn to_gamma(self) -> Self;
@@ -74,7 +316,117 @@ impl ToGamma for f32 {
         if self < 0.003_130_8 {
             self * 12.92
         } else {
-            1.055f32.mul_add(self.powf(1.0 / 2.4), -0.055)
+            float::mul_add(1.055, float::powf(self, 1.0 / 2.4), -0.055)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn packed_u32_test() {
+        assert_eq!(Color32::RED.to_u32(), 0xFF00_00FF);
+        assert_eq!(Color32::from_rgba(0.0, 0.0, 1.0, 0.0).to_u32(), 0x0000_FF00);
+        assert_eq!(
+            Color32::from_rgba(2.0, -1.0, 0.0, 1.0).to_u32(),
+            0xFF00_00FF
+        );
+
+        for packed in [0x0000_0000, 0xFFFF_FFFF, 0x3366_99FF, 0x1234_5678] {
+            assert_eq!(Color32::from_u32(packed).to_u32(), packed);
         }
+
+        assert_eq!(Color32::from_u32(0x00FF_00FF), Color32::GREEN);
+    }
+
+    #[test]
+    fn rgba_u8_test() {
+        let gray = Color32::from_rgba(0.5, 0.5, 0.5, 0.5);
+
+        assert_eq!(gray.as_rgba_u8(), [128, 128, 128, 128]);
+        //gamma encoding brightens the mid tones, but not alpha
+        assert_eq!(gray.as_rgba_u8_srgb(), [188, 188, 188, 128]);
+
+        assert_eq!(Color32::WHITE.as_rgba_u8_srgb(), [255; 4]);
+        assert_eq!(Color32::BLACK.as_rgba_u8_srgb(), [0, 0, 0, 255]);
+        assert_eq!(
+            Color32::from_rgba(2.0, -1.0, 0.0, 1.0).as_rgba_u8(),
+            [255, 0, 0, 255]
+        );
+    }
+
+    #[test]
+    fn color_blindness_test() {
+        let close = |a: [f32; 3], b: [f32; 3]| a.iter().zip(b).all(|(a, b)| (a - b).abs() < 0.01);
+
+        //red turns into a dark olive, with red and green in balance
+        let red = Color32::RED.simulate_color_blindness(ColorBlindness::Protanopia);
+        assert!(close(red.as_rgb(), [0.112, 0.112, 0.004]));
+
+        //red and green become hard to tell apart
+        let [r, g, _] = Color32::RED
+            .simulate_color_blindness(ColorBlindness::Deuteranopia)
+            .as_rgb();
+        assert!((r - g).abs() < 0.1);
+
+        let gray = Color32::from_rgba(0.5, 0.5, 0.5, 0.25);
+        for kind in [
+            ColorBlindness::Protanopia,
+            ColorBlindness::Deuteranopia,
+            ColorBlindness::Tritanopia,
+        ] {
+            let simulated = gray.simulate_color_blindness(kind);
+            assert!(close(simulated.as_rgb(), gray.as_rgb()));
+            assert!((simulated.as_rgba()[3] - 0.25).abs() < f32::EPSILON);
+        }
+    }
+
+    #[test]
+    fn nearest_test() {
+        let palette = [Color32::RED, Color32::GREEN, Color32::BLUE];
+        let off_red = Color32::from_rgb(0.9, 0.1, 0.05);
+
+        assert_eq!(off_red.nearest(&palette), Some(&Color32::RED));
+        assert_eq!(Color32::BLUE.nearest(&palette), Some(&Color32::BLUE));
+        assert_eq!(off_red.nearest(&[]), None);
+
+        assert!((Color32::RED.distance_squared(&Color32::GREEN) - 2.0).abs() < f32::EPSILON);
+        //alpha doesn't count
+        let transparent_red = Color32::from_rgba(1.0, 0.0, 0.0, 0.0);
+        assert!(Color32::RED.distance_squared(&transparent_red).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn mix_subtractive_test() {
+        let yellow = Color32::YELLOW;
+
+        //endpoints stay the same
+        assert_eq!(Color32::RED.mix_subtractive(&yellow, 0.0), Color32::RED);
+        assert_eq!(Color32::RED.mix_subtractive(&yellow, 1.0), yellow);
+
+        //red and yellow give orange
+        let [r, g, b] = Color32::RED.mix_subtractive(&yellow, 0.5).as_rgb();
+        assert!(r > g && g > b);
+        assert!(g > 0.1);
+
+        //red and blue give purple, instead of a darker magenta
+        let [r, g, b] = Color32::RED.mix_subtractive(&Color32::BLUE, 0.5).as_rgb();
+        assert!((r - b).abs() < 0.01);
+        assert!(r > 0.2);
+        assert!(g < 0.01);
+    }
+
+    #[test]
+    fn f32_slice_test() {
+        let colors = [Color32::RED, Color32::from_rgba(0.1, 0.2, 0.3, 0.4)];
+
+        let channels = Color32::slice_as_f32(&colors);
+        assert_eq!(channels, &[1.0, 0.0, 0.0, 1.0, 0.1, 0.2, 0.3, 0.4]);
+        assert_eq!(Color32::from_f32_slice(channels), Some(&colors[..]));
+
+        assert_eq!(Color32::slice_as_f32(&[]), &[] as &[f32]);
+        assert_eq!(Color32::from_f32_slice(&channels[1..]), None);
     }
 }
@@ -37,8 +37,25 @@
 //!    assert_eq!(old_value, Some("foo"));
 //!    assert_eq!(storage.get(handle_0), None);
 //! ```
-
-use std::marker::PhantomData;
+//!
+//! # Generation exhaustion
+//!
+//! Every reuse of a slot raises its generation. A slot whose generation reached `u32::MAX` is
+//! retired instead of wrapping around, so a stale handle can never alias a newer value. The
+//! retired slot is never reused, leaking the size of one empty value per 2^32 reuses of an index.
+//! Compaction keeps the retired slots in place and moves the values around them.
+
+use alloc::vec::Vec;
+use core::{hash::Hash, marker::PhantomData};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+//slots with this generation are retired instead of reused
+#[cfg(not(test))]
+const GENERATION_CAP: u32 = u32::MAX;
+//low enough for the tests to exhaust a slot
+#[cfg(test)]
+const GENERATION_CAP: u32 = 3;
 
 /// Handle to a value inserted into the `GenVec`
 pub struct Handle<K> {
@@ -66,6 +83,20 @@ impl<K> PartialEq for Handle<K> {
         self.index == other.index && self.generation == other.generation
     }
 }
+impl<K> Hash for Handle<K> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.generation.hash(state);
+    }
+}
+impl<K> core::fmt::Debug for Handle<K> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Handle")
+            .field("index", &self.index)
+            .field("generation", &self.generation)
+            .finish()
+    }
+}
 
 /// Storage for values that invalidates handles to them once the values are removed/replaces
 ///
@@ -76,6 +107,8 @@ impl<K> PartialEq for Handle<K> {
 pub struct GenVec<K, V> {
     values: Vec<Value<V>>,
     free: Vec<usize>,
+    //generation of newly pushed values, raised by compaction so that old handles stay invalid
+    generation_floor: u32,
     phantom: PhantomData<K>,
 }
 
@@ -99,6 +132,7 @@ impl<K, V> GenVec<K, V> {
         Self {
             values: Vec::new(),
             free: Vec::new(),
+            generation_floor: 0,
             phantom: PhantomData,
         }
     }
@@ -119,6 +153,7 @@ impl<K, V> GenVec<K, V> {
         Self {
             values: Vec::with_capacity(capacity),
             free: Vec::with_capacity(capacity),
+            generation_floor: 0,
             phantom: PhantomData,
         }
     }
@@ -201,7 +236,71 @@ impl<K, V> GenVec<K, V> {
             .and_then(|r| r.value.as_mut())
     }
 
+    /// Gets the immutable reference to the value associated with the `Handle`, without checking
+    /// if the handle is still valid.
+    ///
+    /// Only meant for hot paths that already checked the handle, for example with `get`, in the
+    /// same scope. Prefer `get` everywhere else.
+    ///
+    /// # Safety
+    /// The handle must be valid: it was returned by `insert` of this collection and the value
+    /// wasn't removed or compacted since. Calling this with an invalid handle is undefined
+    /// behavior.
+    /// ```
+    /// use cac_core::gen_vec;
+    ///
+    /// struct Key;
+    /// let mut storage = gen_vec::GenVec::<Key, _>::new();
+    /// let handle = storage.insert("foo");
+    ///
+    /// if storage.get(handle).is_some() {
+    ///     //SAFETY: the handle was checked above
+    ///     assert_eq!(unsafe { storage.get_unchecked(handle) }, &"foo");
+    /// }
+    /// ```
+    #[must_use]
+    pub unsafe fn get_unchecked(&self, handle: Handle<K>) -> &V {
+        debug_assert!(self.get(handle).is_some(), "invalid handle");
+        self.values
+            .get_unchecked(handle.index)
+            .value
+            .as_ref()
+            .unwrap_unchecked()
+    }
+
+    /// Gets the mutable reference to the value associated with the `Handle`, without checking
+    /// if the handle is still valid. See `get_unchecked`.
+    ///
+    /// # Safety
+    /// The handle must be valid, see `get_unchecked`.
+    /// ```
+    /// use cac_core::gen_vec;
+    ///
+    /// struct Key;
+    /// let mut storage = gen_vec::GenVec::<Key, _>::new();
+    /// let handle = storage.insert(5);
+    ///
+    /// if storage.get(handle).is_some() {
+    ///     //SAFETY: the handle was checked above
+    ///     *unsafe { storage.get_unchecked_mut(handle) } *= 3;
+    /// }
+    ///
+    /// assert_eq!(storage.get(handle), Some(&15));
+    /// ```
+    #[must_use]
+    pub unsafe fn get_unchecked_mut(&mut self, handle: Handle<K>) -> &mut V {
+        debug_assert!(self.get(handle).is_some(), "invalid handle");
+        self.values
+            .get_unchecked_mut(handle.index)
+            .value
+            .as_mut()
+            .unwrap_unchecked()
+    }
+
     /// Inserts a new value into the collection and returns a `Handle` to it.
+    ///
+    /// Free slots whose generation would wrap around are retired permanently instead of being
+    /// reused.
     /// ```
     /// use cac_core::gen_vec;
     ///
@@ -216,33 +315,38 @@ impl<K, V> GenVec<K, V> {
     /// ```
     pub fn insert(&mut self, value: V) -> Handle<K> {
         //take an index out of the "free" vec, or push a new value
-        if let Some(index) = self.free.pop() {
+        while let Some(index) = self.free.pop() {
             let v = self
                 .values
                 .get_mut(index)
                 .expect("The free list should be unable to store indices that are out of bounds!");
 
+            //the slot is exhausted, wrapping could alias a stale handle
+            if v.generation == GENERATION_CAP {
+                continue;
+            }
+
             v.generation += 1;
             v.value = Some(value);
 
-            Handle {
+            return Handle {
                 index,
                 generation: v.generation,
                 phantom: PhantomData,
-            }
-        } else {
-            let index = self.values.len();
-            let generation = 0;
-            self.values.push(Value {
-                value: Some(value),
-                generation,
-            });
+            };
+        }
 
-            Handle {
-                index,
-                generation,
-                phantom: PhantomData,
-            }
+        let index = self.values.len();
+        let generation = self.generation_floor;
+        self.values.push(Value {
+            value: Some(value),
+            generation,
+        });
+
+        Handle {
+            index,
+            generation,
+            phantom: PhantomData,
         }
     }
 
@@ -274,6 +378,107 @@ impl<K, V> GenVec<K, V> {
                 v.value.take()
             })
     }
+
+    /// Moves all values to the front of the collection, removing the free slots in between, and
+    /// shrinks the storage. Retired slots keep their index, see the module documentation.
+    ///
+    /// All existing handles are invalidated. The returned map contains the new handle for every
+    /// old handle to a value, so stored handles have to be updated with it.
+    ///
+    /// Only available with the `std` feature.
+    /// ```
+    /// use cac_core::gen_vec;
+    ///
+    /// struct Key;
+    /// let mut storage = gen_vec::GenVec::<Key, _>::new();
+    ///
+    /// let handle_0 = storage.insert("foo");
+    /// let handle_1 = storage.insert("bar");
+    /// storage.remove(handle_0);
+    ///
+    /// let remapped = storage.compact();
+    /// let handle_1 = remapped[&handle_1];
+    ///
+    /// assert_eq!(storage.get(handle_1), Some(&"bar"));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn compact(&mut self) -> HashMap<Handle<K>, Handle<K>> {
+        //every handle before the compaction has a lower generation, so none of them can alias
+        //a moved value
+        //retired slots are skipped, their generation can't be exceeded. They keep their index
+        //instead, so their stale handles can't alias a moved value. Every other generation is
+        //below the cap, so the floor reaches the cap at most.
+        let generation = self
+            .values
+            .iter()
+            .map(|v| v.generation)
+            .filter(|g| *g != GENERATION_CAP)
+            .max()
+            .map_or(self.generation_floor, |g| {
+                (g + 1).max(self.generation_floor)
+            });
+
+        let old_values = core::mem::take(&mut self.values);
+        let mut remapped = HashMap::with_capacity(old_values.len().saturating_sub(self.free.len()));
+        let mut retired = old_values
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| v.generation == GENERATION_CAP)
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .peekable();
+        self.free.clear();
+
+        for (old_index, old) in old_values.into_iter().enumerate() {
+            if let Some(value) = old.value {
+                while retired.next_if_eq(&self.values.len()).is_some() {
+                    self.values.push(Value {
+                        value: None,
+                        generation: GENERATION_CAP,
+                    });
+                }
+
+                let new_handle = Handle {
+                    index: self.values.len(),
+                    generation,
+                    phantom: PhantomData,
+                };
+                let old_handle = Handle {
+                    index: old_index,
+                    generation: old.generation,
+                    phantom: PhantomData,
+                };
+
+                self.values.push(Value {
+                    value: Some(value),
+                    generation,
+                });
+                remapped.insert(old_handle, new_handle);
+            }
+        }
+
+        //the slots between the remaining retired ones are free
+        for index in retired {
+            while self.values.len() < index {
+                self.free.push(self.values.len());
+                self.values.push(Value {
+                    value: None,
+                    generation,
+                });
+            }
+            self.values.push(Value {
+                value: None,
+                generation: GENERATION_CAP,
+            });
+        }
+
+        self.free.shrink_to_fit();
+        self.values.shrink_to_fit();
+        self.generation_floor = generation;
+
+        remapped
+    }
 }
 
 impl<K, V> Default for GenVec<K, V> {
@@ -373,6 +578,27 @@ pub mod test {
         assert_eq!(storage.get(handle), storage.get(handle_copy));
     }
 
+    #[test]
+    fn get_unchecked_test() {
+        let (handles, mut storage) = test_storage::<10>();
+        storage.remove(handles[3]);
+        let reused = storage.insert("Reused".to_owned());
+
+        for h in handles
+            .iter()
+            .filter(|h| **h != handles[3])
+            .chain([&reused])
+        {
+            let checked = storage.get(*h).cloned();
+            //SAFETY: only the removed handle is invalid
+            let unchecked = unsafe { storage.get_unchecked(*h) };
+            assert_eq!(checked.as_ref(), Some(unchecked));
+
+            unsafe { storage.get_unchecked_mut(*h) }.push('!');
+            assert_eq!(storage.get(*h), checked.map(|v| v + "!").as_ref());
+        }
+    }
+
     #[test]
     fn insert_over_capacity_test() {
         let mut storage: GenVec<String, String> = GenVec::with_capacity(3);
@@ -381,4 +607,97 @@ pub mod test {
             storage.insert(format!("{i}"));
         }
     }
+
+    #[test]
+    fn generation_exhaustion_test() {
+        let mut storage: GenVec<String, String> = GenVec::new();
+
+        let mut stale = Vec::new();
+        let mut handle = storage.insert("Value0".to_owned());
+        for i in 1..10 {
+            stale.push(handle);
+            storage.remove(handle);
+            handle = storage.insert(format!("Value{i}"));
+        }
+
+        //the first slot is retired after reaching the cap, instead of wrapping to generation 0
+        assert_eq!(storage.values.len(), 3);
+        assert_eq!(storage.values[0].generation, 3);
+        assert!(storage.values[0].value.is_none());
+
+        for h in &stale {
+            assert_eq!(storage.get(*h), None);
+        }
+        assert_eq!(storage.get(handle), Some(&"Value9".to_owned()));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn compact_test() {
+        let (handles, mut storage) = test_storage::<10>();
+
+        for h in handles.iter().step_by(2) {
+            storage.remove(*h);
+        }
+
+        let remapped = storage.compact();
+        assert_eq!(remapped.len(), 5);
+        assert_eq!(storage.values.len(), 5);
+
+        for (i, h) in handles.iter().enumerate() {
+            //old handles are invalidated, even if they point into the compacted range
+            assert_eq!(storage.get(*h), None);
+
+            if i % 2 == 1 {
+                let new_handle = remapped[h];
+                assert_eq!(storage.get(new_handle), Some(&format!("Value{i}")));
+            } else {
+                assert!(!remapped.contains_key(h));
+            }
+        }
+
+        //new values don't alias old handles either
+        let new_handles: Vec<_> = (0..10).map(|i| storage.insert(format!("New{i}"))).collect();
+        for h in &handles {
+            assert_eq!(storage.get(*h), None);
+        }
+        for (i, h) in new_handles.iter().enumerate() {
+            assert_eq!(storage.get(*h), Some(&format!("New{i}")));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn compact_retired_test() {
+        let mut storage: GenVec<String, String> = GenVec::new();
+
+        //retire the first slot and raise the second one right below the cap
+        let mut retired = storage.insert("Retired".to_owned());
+        let mut kept = storage.insert("Kept".to_owned());
+        for _ in 0..GENERATION_CAP {
+            storage.remove(retired);
+            retired = storage.insert("Retired".to_owned());
+        }
+        storage.remove(retired);
+        for _ in 1..GENERATION_CAP {
+            storage.remove(kept);
+            kept = storage.insert("Kept".to_owned());
+        }
+        assert_eq!(storage.values[0].generation, GENERATION_CAP);
+
+        //the retired slot stays in place, the floor saturates at the cap
+        let remapped = storage.compact();
+        let kept = remapped[&kept];
+        assert_eq!(kept.index, 1);
+        assert_eq!(storage.get(kept), Some(&"Kept".to_owned()));
+        assert_eq!(storage.get(retired), None);
+
+        //a value at the saturated floor is retired instead of reused
+        storage.remove(kept);
+        let new_handle = storage.insert("New".to_owned());
+        assert_eq!(storage.get(kept), None);
+        assert_eq!(storage.get(retired), None);
+        assert_eq!(storage.get(new_handle), Some(&"New".to_owned()));
+        assert_eq!(storage.values.len(), 3);
+    }
 }
@@ -1,5 +1,7 @@
 use std::fmt::Display;
 
+use crate::{math::URect, Color32};
+
 /// Range in which color channels are assumed to be equal, to avoid floating point accuracies.
 /// For example, the difference between RGB(0.92, 0.32, 0.34) and RGB(0.91, 0.33, 0.33) is negligable
 
@@ -25,6 +27,158 @@ pub enum Format {
     RgbF32,
     RgbaU8,
     RgbaF32,
+    /// Single channel, like masks or heightmaps
+    RedU8,
+    /// Single channel, like masks or heightmaps
+    RedF32,
+}
+
+/// Compositing modes of `Image::blend`, like the layer modes of image editors
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum BlendMode {
+    /// The top layer covers the bottom layer
+    #[default]
+    Normal,
+    /// Darkens, white keeps the bottom layer unchanged and black results in black
+    Multiply,
+    /// Lightens, the inverse of multiply
+    Screen,
+    /// Multiplies the dark and screens the light areas of the bottom layer, increasing contrast
+    Overlay,
+    /// Adds the layers, clamped to 1.0
+    Add,
+}
+
+impl BlendMode {
+    fn apply(self, bottom: f32, top: f32) -> f32 {
+        match self {
+            Self::Normal => top,
+            Self::Multiply => bottom * top,
+            Self::Screen => (1.0 - bottom).mul_add(top - 1.0, 1.0),
+            Self::Overlay if bottom < 0.5 => 2.0 * bottom * top,
+            Self::Overlay => (2.0 * (1.0 - bottom)).mul_add(top - 1.0, 1.0),
+            Self::Add => (bottom + top).min(1.0),
+        }
+    }
+}
+
+/// Color lookup table in the shape of a rgb cube, used for color grading
+///
+/// The colors are ordered with red changing fastest, then green and then blue, like in `.cube`
+/// files.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lut3D {
+    size: usize,
+    colors: Vec<Color32>,
+}
+
+impl Lut3D {
+    /// Constructor
+    ///
+    /// # Errors
+    /// `DimensionMismatch` when the size is less than 2 or there aren't exactly `size`³ colors.
+    pub fn new(size: usize, colors: Vec<Color32>) -> Result<Self, Error> {
+        if colors.len() != Self::color_count(size)? {
+            return Err(Error::DimensionMismatch);
+        }
+
+        Ok(Self { size, colors })
+    }
+
+    /// Number of colors of a table with the size, `size`³.
+    ///
+    /// # Errors
+    /// `DimensionMismatch` when the size is less than 2 or the count doesn't fit into usize.
+    fn color_count(size: usize) -> Result<usize, Error> {
+        if size < 2 {
+            return Err(Error::DimensionMismatch);
+        }
+
+        size.checked_mul(size)
+            .and_then(|s| s.checked_mul(size))
+            .ok_or(Error::DimensionMismatch)
+    }
+
+    /// Creates a table that maps every color onto itself.
+    ///
+    /// # Errors
+    /// `DimensionMismatch` when the size is less than 2 or too large.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn identity(size: usize) -> Result<Self, Error> {
+        //validated before generating the colors, so a huge size fails instead of allocating
+        Self::color_count(size)?;
+        let max = (size - 1) as f32;
+        let colors = (0..size)
+            .flat_map(|b| (0..size).flat_map(move |g| (0..size).map(move |r| (r, g, b))))
+            .map(|(r, g, b)| Color32::from_rgb(r as f32 / max, g as f32 / max, b as f32 / max))
+            .collect();
+
+        Self::new(size, colors)
+    }
+
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Trilinearly interpolated color at the rgb position inside the cube.
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn sample(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let max = (self.size - 1) as f32;
+
+        //lower corner and the weight of the upper corner on each axis
+        let corners = rgb.map(|c| {
+            let position = c.clamp(0.0, 1.0) * max;
+            let lower = (position.floor() as usize).min(self.size - 2);
+            (lower, position - lower as f32)
+        });
+        let [red, green, blue] =
+            corners.map(|(lower, weight)| [(lower, 1.0 - weight), (lower + 1, weight)]);
+
+        let mut color = [0.0; 3];
+        for (blue_index, blue_weight) in blue {
+            for (green_index, green_weight) in green {
+                for (red_index, red_weight) in red {
+                    let index = red_index + (green_index + blue_index * self.size) * self.size;
+                    let weight = red_weight * green_weight * blue_weight;
+
+                    color
+                        .iter_mut()
+                        .zip(self.colors[index].as_rgb())
+                        .for_each(|(channel, corner)| *channel = corner.mul_add(weight, *channel));
+                }
+            }
+        }
+
+        color
+    }
+}
+
+/// Sampling filter used when resizing images
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum Filter {
+    /// Takes the closest pixel, fast but prone to aliasing
+    #[default]
+    Nearest,
+    /// Catmull-Rom bicubic sampling, slow but keeps edges sharp
+    Bicubic,
+}
+
+/// Per channel statistics of an image, see `Image::statistics`.
+///
+/// The channels are rgba, normalized to 0.0 - 1.0 for u8 formats. Formats without alpha count as
+/// opaque, red formats have no green and blue.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageStats {
+    pub mean: [f32; 4],
+    pub min: [f32; 4],
+    pub max: [f32; 4],
+    /// Population variance, the mean of the squared differences to the mean
+    pub variance: [f32; 4],
 }
 
 #[derive(Debug)]
@@ -34,6 +188,7 @@ pub enum Error {
     UpScalingNotSupported,
     DimensionMismatch,
     EncodingFailed(String),
+    SizeOverflow,
 }
 
 impl std::error::Error for Error {}
@@ -49,6 +204,7 @@ impl Display for Error {
                 "not enough data provided for all channels with width/height"
             ),
             Self::EncodingFailed(e) => write!(f, "encoding failed, caused by {e}"),
+            Self::SizeOverflow => write!(f, "width * height * channels overflows usize"),
         }
     }
 }
@@ -61,8 +217,9 @@ impl Image {
     /// # Errors
     /// `DimensionMismatch` is returned when the length of the data is less than width * height *
     /// channels of the format
+    /// `SizeOverflow` when width * height * channels doesn't fit into usize
     pub fn new(width: u32, height: u32, format: Format, data: Data) -> Result<Self, Error> {
-        if data.len() < width as usize * height as usize * format.channels() as usize {
+        if data.len() < format.data_len(width, height)? {
             Err(Error::DimensionMismatch)
         } else {
             Ok(Self {
@@ -78,6 +235,7 @@ impl Image {
     ///
     /// # Errors
     /// `ConversionFailed` when either the width or the height can't be converted into usize
+    /// `SizeOverflow` when width * height * channels doesn't fit into usize
     #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
     pub fn with_color32(
         width: u32,
@@ -85,19 +243,11 @@ impl Image {
         color: crate::color32::Color32,
         format: Format,
     ) -> Result<Self, Error> {
-        let w: usize = width
-            .try_into()
-            .map_err(|_| Error::ConversionFailed("width to usize"))?;
-
-        let h: usize = height
-            .try_into()
-            .map_err(|_| Error::ConversionFailed("height to usize"))?;
-
         let channels = format.channels().into();
-        let len = w * h * channels;
+        let len = format.data_len(width, height)?;
 
         let data: Data = match format {
-            Format::RgbU8 | Format::RgbaU8 => Data::U8(
+            Format::RgbU8 | Format::RgbaU8 | Format::RedU8 => Data::U8(
                 color.as_rgba()[0..channels]
                     .iter()
                     .map(|v| (v * 255.0).clamp(0.0, 255.0) as u8)
@@ -105,7 +255,7 @@ impl Image {
                     .take(len)
                     .collect(),
             ),
-            Format::RgbF32 | Format::RgbaF32 => Data::F32(
+            Format::RgbF32 | Format::RgbaF32 | Format::RedF32 => Data::F32(
                 color.as_rgba()[0..channels]
                     .iter()
                     .copied()
@@ -118,6 +268,39 @@ impl Image {
         Self::new(width, height, format, data)
     }
 
+    /// Creates a new image by calling the function for every pixel.
+    ///
+    /// The function receives the x and y coordinates of the pixel, starting at the top left, and
+    /// returns its color.
+    ///
+    /// # Errors
+    /// `ConversionFailed` when either the width or the height can't be converted into usize
+    /// `SizeOverflow` when width * height * channels doesn't fit into usize
+    pub fn from_fn(
+        width: u32,
+        height: u32,
+        format: Format,
+        f: impl Fn(u32, u32) -> crate::color32::Color32,
+    ) -> Result<Self, Error> {
+        let channels: usize = format.channels().into();
+
+        let mut image = Self {
+            width,
+            height,
+            format,
+            data: format.create_storage(format.data_len(width, height)? / channels),
+        };
+
+        for y in 0..height {
+            for x in 0..width {
+                let index = image.pixel_index(x, y);
+                image.set_rgba_at(index, f(x, y).as_rgba());
+            }
+        }
+
+        Ok(image)
+    }
+
     /// Generate a 64 bit hash from the image, using a perceibed hash algorithm
     ///
     /// The images gets reduced to a 8x8 grayscale image and then calculates by comparing the
@@ -169,18 +352,246 @@ impl Image {
     /// # Errors
     /// `UpScalingNotSupported` when the new width/height are greater than the old one.
     /// `ConversionFailed` when either width or height can't be converted to usize
+    pub fn resize(&self, new_width: u32, new_height: u32) -> Result<Self, Error> {
+        self.resize_with(new_width, new_height, Filter::Nearest)
+    }
+
+    /// Scale the image using the filter, returning a new image
+    ///
+    /// # Errors
+    /// `UpScalingNotSupported` when the new width/height are greater than the old one.
+    /// `ConversionFailed` when either width or height can't be converted to usize
+    pub fn resize_with(
+        &self,
+        new_width: u32,
+        new_height: u32,
+        filter: Filter,
+    ) -> Result<Self, Error> {
+        if self.width == new_width && self.height == new_height {
+            return Ok(self.clone());
+        } else if new_width > self.width || new_height > self.height {
+            return Err(Error::UpScalingNotSupported);
+        }
+
+        match filter {
+            Filter::Nearest => self.resize_nearest(new_width, new_height),
+            Filter::Bicubic => self.resize_bicubic(new_width, new_height, false),
+        }
+    }
+
+    /// Rotates the image clockwise by the angle around its center, sampling it bilinearly with
+    /// `sample_subpixel`.
+    ///
+    /// The result grows to contain the whole rotated image, the uncovered corners are filled with
+    /// the fill color.
+    ///
+    /// # Errors
+    /// `ConversionFailed` when either the width or the height can't be converted into usize
+    /// `SizeOverflow` when width * height * channels doesn't fit into usize
     #[allow(
         clippy::cast_precision_loss,
         clippy::cast_possible_truncation,
         clippy::cast_sign_loss
     )]
-    pub fn resize(&self, new_width: u32, new_height: u32) -> Result<Self, Error> {
+    pub fn rotate(&self, degrees: f32, fill: Color32) -> Result<Self, Error> {
+        //coordinates this close to the edge are rounding errors, like the cosine of 90 degrees
+        const TOLERANCE: f32 = 0.001;
+
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let (width, height) = (self.width as f32, self.height as f32);
+        let rotated_width = width.mul_add(cos.abs(), height * sin.abs());
+        let rotated_height = width.mul_add(sin.abs(), height * cos.abs());
+        let new_width = (rotated_width - TOLERANCE).ceil().max(0.0) as u32;
+        let new_height = (rotated_height - TOLERANCE).ceil().max(0.0) as u32;
+
+        //pixel centers are at whole coordinates
+        let center = ((width - 1.0) / 2.0, (height - 1.0) / 2.0);
+        let new_center = (
+            (new_width as f32 - 1.0) / 2.0,
+            (new_height as f32 - 1.0) / 2.0,
+        );
+        let snap = |position: f32, max: f32| {
+            if (-TOLERANCE..=max + TOLERANCE).contains(&position) {
+                position.clamp(0.0, max)
+            } else {
+                position
+            }
+        };
+
+        Self::from_fn(new_width, new_height, self.format, |x, y| {
+            let (dx, dy) = (x as f32 - new_center.0, y as f32 - new_center.1);
+            let source_x = cos.mul_add(dx, sin * dy) + center.0;
+            let source_y = cos.mul_add(dy, -sin * dx) + center.1;
+
+            self.sample_subpixel(snap(source_x, width - 1.0), snap(source_y, height - 1.0))
+                .unwrap_or(fill)
+        })
+    }
+
+    /// Places the images next to each other, from left to right, for example to compare an
+    /// actual and an expected render side by side.
+    ///
+    /// # Errors
+    /// `DimensionMismatch` when there are no images or they don't have the same height and format
+    /// `SizeOverflow` when the combined width or its data length overflows
+    pub fn concat_horizontal(images: &[&Self]) -> Result<Self, Error> {
+        let first = images.first().ok_or(Error::DimensionMismatch)?;
+        if images
+            .iter()
+            .any(|image| image.height != first.height || image.format != first.format)
+        {
+            return Err(Error::DimensionMismatch);
+        }
+
+        let width = images
+            .iter()
+            .try_fold(0_u32, |width, image| width.checked_add(image.width))
+            .ok_or(Error::SizeOverflow)?;
+        let rows = first
+            .height
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("height to usize"))?;
+
+        let data = Self::concat_rows(images, width, first.height, rows, |image| {
+            image.format.data_len(image.width, 1)
+        })?;
+        Self::new(width, first.height, first.format, data)
+    }
+
+    /// Places the images below each other, from top to bottom, see `concat_horizontal`.
+    ///
+    /// # Errors
+    /// `DimensionMismatch` when there are no images or they don't have the same width and format
+    /// `SizeOverflow` when the combined height or its data length overflows
+    pub fn concat_vertical(images: &[&Self]) -> Result<Self, Error> {
+        let first = images.first().ok_or(Error::DimensionMismatch)?;
+        if images
+            .iter()
+            .any(|image| image.width != first.width || image.format != first.format)
+        {
+            return Err(Error::DimensionMismatch);
+        }
+
+        let height = images
+            .iter()
+            .try_fold(0_u32, |height, image| height.checked_add(image.height))
+            .ok_or(Error::SizeOverflow)?;
+
+        //the rows are contiguous, so each image is copied as one block
+        let data = Self::concat_rows(images, first.width, height, 1, |image| {
+            image.format.data_len(image.width, image.height)
+        })?;
+        Self::new(first.width, height, first.format, data)
+    }
+
+    /// Copies the rows of the images into a combined image of the size, taking one row of each
+    /// image in turn. The images are expected to have the same format.
+    fn concat_rows(
+        images: &[&Self],
+        width: u32,
+        height: u32,
+        rows: usize,
+        row_len: impl Fn(&Self) -> Result<usize, Error>,
+    ) -> Result<Data, Error> {
+        let format = images.first().ok_or(Error::DimensionMismatch)?.format;
+        let channels: usize = format.channels().into();
+        let mut data = format.create_storage(format.data_len(width, height)? / channels);
+
+        let mut start = 0;
+        for row in 0..rows {
+            for image in images {
+                let len = row_len(image)?;
+                let source = row * len..(row + 1) * len;
+                let target = start..start + len;
+
+                match (&mut data, &image.data) {
+                    (Data::U8(data), Data::U8(image)) => {
+                        data[target].copy_from_slice(&image[source]);
+                    }
+                    (Data::F32(data), Data::F32(image)) => {
+                        data[target].copy_from_slice(&image[source]);
+                    }
+                    _ => return Err(Error::DimensionMismatch),
+                }
+                start += len;
+            }
+        }
+
+        Ok(data)
+    }
+
+    /// Halves the size of the image, averaging each 2x2 block of pixels into one, like a single
+    /// level of a mipmap chain.
+    ///
+    /// Odd widths and heights are rounded up, the blocks on the last column or row are clamped
+    /// to the edge, so they average the edge pixels with themselves.
+    ///
+    /// # Errors
+    /// `ConversionFailed` when either the width or the height can't be converted into usize
+    /// `SizeOverflow` when width * height * channels doesn't fit into usize
+    pub fn downsample_2x(&self) -> Result<Self, Error> {
+        let (max_x, max_y) = (self.width.saturating_sub(1), self.height.saturating_sub(1));
+
+        Self::from_fn(
+            self.width.div_ceil(2),
+            self.height.div_ceil(2),
+            self.format,
+            |x, y| {
+                let (left, top) = (x * 2, y * 2);
+                let block = [
+                    (left, top),
+                    ((left + 1).min(max_x), top),
+                    (left, (top + 1).min(max_y)),
+                    ((left + 1).min(max_x), (top + 1).min(max_y)),
+                ];
+
+                let mut sum = [0.0; 4];
+                for (column, row) in block {
+                    let rgba = self.rgba_at(self.pixel_index(column, row));
+                    sum.iter_mut().zip(rgba).for_each(|(s, c)| *s += c);
+                }
+
+                let [red, green, blue, alpha] = sum.map(|s| s / 4.0);
+                Color32::from_rgba(red, green, blue, alpha)
+            },
+        )
+    }
+
+    /// Scale the image using the filter, interpolating the colors premultiplied by their alpha.
+    ///
+    /// With straight alpha, the color of transparent pixels bleeds into their neighbors, like
+    /// dark halos around the edges of sprites on a transparent black background. Weighting the
+    /// colors by their alpha avoids that. Images without alpha channel are resized like with
+    /// `resize_with`.
+    ///
+    /// # Errors
+    /// `UpScalingNotSupported` when the new width/height are greater than the old one.
+    /// `ConversionFailed` when either width or height can't be converted to usize
+    pub fn resize_premultiplied(
+        &self,
+        new_width: u32,
+        new_height: u32,
+        filter: Filter,
+    ) -> Result<Self, Error> {
         if self.width == new_width && self.height == new_height {
             return Ok(self.clone());
         } else if new_width > self.width || new_height > self.height {
             return Err(Error::UpScalingNotSupported);
         }
 
+        match filter {
+            //nearest neighbors don't mix colors
+            Filter::Nearest => self.resize_nearest(new_width, new_height),
+            Filter::Bicubic => self.resize_bicubic(new_width, new_height, true),
+        }
+    }
+
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn resize_nearest(&self, new_width: u32, new_height: u32) -> Result<Self, Error> {
         let width: usize = new_width
             .try_into()
             .map_err(|_| Error::ConversionFailed("width to usize"))?;
@@ -230,6 +641,66 @@ impl Image {
         })
     }
 
+    fn resize_bicubic(
+        &self,
+        new_width: u32,
+        new_height: u32,
+        premultiply: bool,
+    ) -> Result<Self, Error> {
+        let width: usize = new_width
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("width to usize"))?;
+
+        let height: usize = new_height
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("height to usize"))?;
+
+        let columns: Vec<_> = (0..new_width)
+            .map(|x| bicubic_weights(x, self.width, new_width))
+            .collect();
+        let rows: Vec<_> = (0..new_height)
+            .map(|y| bicubic_weights(y, self.height, new_height))
+            .collect();
+
+        let mut image = Self {
+            width: new_width,
+            height: new_height,
+            format: self.format,
+            data: self.format.create_storage(width * height),
+        };
+
+        for (y, row) in (0..new_height).zip(&rows) {
+            for (x, column) in (0..new_width).zip(&columns) {
+                let mut rgba = [0.0; 4];
+                for (source_y, weight_y) in row {
+                    for (source_x, weight_x) in column {
+                        let mut source = self.rgba_at(self.pixel_index(*source_x, *source_y));
+                        if premultiply {
+                            let alpha = source[3];
+                            source[..3].iter_mut().for_each(|c| *c *= alpha);
+                        }
+
+                        rgba.iter_mut()
+                            .zip(source)
+                            .for_each(|(c, s)| *c += s * weight_x * weight_y);
+                    }
+                }
+
+                if premultiply {
+                    let alpha = rgba[3];
+                    rgba[..3]
+                        .iter_mut()
+                        .for_each(|c| *c = if alpha > EPSILON { *c / alpha } else { 0.0 });
+                }
+
+                let index = image.pixel_index(x, y);
+                image.set_rgba_at(index, rgba);
+            }
+        }
+
+        Ok(image)
+    }
+
     #[must_use]
     pub fn sample(&self, pixel_x: u32, pixel_y: u32) -> Option<Pixel> {
         if pixel_x >= self.width || pixel_y >= self.height {
@@ -261,108 +732,847 @@ impl Image {
         }
     }
 
-    /// Constructor
-    /// Creates a new image from the raw bytes of an image file.
-    /// Currently, only the png format is supported.
-    /// For raw pixel data, see `new` instead.
+    /// Samples the image at fractional pixel coordinates, bilinearly interpolating the four
+    /// surrounding pixels, for example for warping effects.
     ///
-    /// # Errors
-    /// `FileNotFound` when the bytes don't match a valid file
-    pub fn load_from_memory(format: Format, data: &[u8]) -> Result<Self, Error> {
-        let img = image::load_from_memory(data);
+    /// Pixel centers are at whole coordinates, so (0.5, 0.5) is the average of the four top left
+    /// pixels. Returns `None` when the coordinates are outside of the pixel centers, from 0.0 to
+    /// width - 1 and height - 1.
+    #[must_use]
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    pub fn sample_subpixel(&self, x: f32, y: f32) -> Option<Color32> {
+        let max_x = self.width.checked_sub(1)? as f32;
+        let max_y = self.height.checked_sub(1)? as f32;
+        if !(0.0..=max_x).contains(&x) || !(0.0..=max_y).contains(&y) {
+            return None;
+        }
 
-        match img {
-            Ok(img) => {
-                let data = match format {
-                    Format::RgbU8 => Data::U8(img.to_rgb8().to_vec()),
-                    Format::RgbaU8 => Data::U8(img.to_rgba8().to_vec()),
-                    Format::RgbF32 => Data::F32(img.to_rgb32f().to_vec()),
-                    Format::RgbaF32 => Data::F32(img.to_rgba32f().to_vec()),
-                };
+        let (left, top) = (x.floor() as u32, y.floor() as u32);
+        let right = (left + 1).min(self.width - 1);
+        let bottom = (top + 1).min(self.height - 1);
+        let (tx, ty) = (x - x.floor(), y - y.floor());
+
+        let lerp = |from: [f32; 4], to: [f32; 4], t: f32| {
+            [0, 1, 2, 3].map(|channel| (to[channel] - from[channel]).mul_add(t, from[channel]))
+        };
+        let at = |column, row| self.rgba_at(self.pixel_index(column, row));
+
+        let upper = lerp(at(left, top), at(right, top), tx);
+        let lower = lerp(at(left, bottom), at(right, bottom), tx);
+        let [red, green, blue, alpha] = lerp(upper, lower, ty);
+
+        Some(Color32::from_rgba(red, green, blue, alpha))
+    }
+
+    /// Draws a grid of one pixel wide lines over the image, every `spacing` pixels starting at the
+    /// top left, for checking texture coordinates in rendered output.
+    ///
+    /// A spacing of 0 leaves the image unchanged.
+    pub fn draw_grid(&mut self, spacing: u32, color: Color32) {
+        if spacing == 0 {
+            return;
+        }
 
-                Self::new(img.width(), img.height(), format, data)
+        let rgba = color.as_rgba();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if x % spacing == 0 || y % spacing == 0 {
+                    let index = self.pixel_index(x, y);
+                    self.set_rgba_at(index, rgba);
+                }
             }
-            Err(e) => Err(Error::FileNotFound(e.to_string())),
         }
     }
 
-    /// Constructor
-    /// Creates a new image from an image file.
-    /// Currently, only the png format is supported.
+    /// Finds the region in which the two images differ.
+    ///
+    /// Returns the bounding box of all pixels that have at least one channel differing by more
+    /// than the tolerance, or `None` when the images are equal within the tolerance.
+    /// Channels are compared as normalized values(0.0 - 1.0), so images with different formats can
+    /// be compared as well. Missing alpha channels are treated as opaque.
     ///
     /// # Errors
-    /// `FileNotFound` when the file can't be found/loaded.
-    pub fn load_from_file(
-        format: Format,
-        path: impl AsRef<std::path::Path>,
-    ) -> Result<Self, Error> {
-        let img = image::open(path);
+    /// `DimensionMismatch` when the images don't have the same width and height
+    pub fn difference_region(&self, other: &Self, tolerance: f32) -> Result<Option<URect>, Error> {
+        if self.width != other.width || self.height != other.height {
+            return Err(Error::DimensionMismatch);
+        }
 
-        match img {
-            Ok(img) => {
-                let data = match format {
-                    Format::RgbU8 => Data::U8(img.to_rgb8().to_vec()),
-                    Format::RgbaU8 => Data::U8(img.to_rgba8().to_vec()),
-                    Format::RgbF32 => Data::F32(img.to_rgb32f().to_vec()),
-                    Format::RgbaF32 => Data::F32(img.to_rgba32f().to_vec()),
-                };
+        let mut region: Option<(u32, u32, u32, u32)> = None;
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let index = self.pixel_index(x, y);
+                let differs = self
+                    .rgba_at(index)
+                    .iter()
+                    .zip(other.rgba_at(index))
+                    .any(|(a, b)| (a - b).abs() > tolerance);
 
-                Self::new(img.width(), img.height(), format, data)
+                if differs {
+                    region = Some(region.map_or((x, y, x, y), |(x0, y0, x1, y1)| {
+                        (x0.min(x), y0.min(y), x1.max(x), y1.max(y))
+                    }));
+                }
             }
-            Err(e) => Err(Error::FileNotFound(e.to_string())),
         }
+
+        Ok(region.map(|(x0, y0, x1, y1)| URect::with_points((x0, y0), (x1 + 1, y1 + 1))))
     }
 
-    /// Saves the image to disk in the png format.
+    /// Maps each channel through a tone curve, for example for gamma or contrast adjustments.
+    ///
+    /// The lookup table is spread evenly over 0.0 - 1.0 and linearly interpolated. Red values
+    /// are mapped to the red channel of the table, green to green and blue to blue. Alpha stays
+    /// the same.
     ///
     /// # Errors
-    /// `EncodingFailed` when the image couldn't be encoded into one of the supported file
-    /// formats(right now it's only png)
-    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-    pub fn save_to_file(&self, path: impl AsRef<std::path::Path>) -> Result<(), Error> {
-        let img_data = match &self.data {
-            Data::U8(data) => data.clone(),
-            Data::F32(data) => data
-                .iter()
-                .map(|v| ((255.0 * v.clamp(0.0, 255.0)) as u8))
-                .collect(),
+    /// `DimensionMismatch` when the lookup table is empty
+    /// `ConversionFailed` when either the width or the height can't be converted into usize
+    /// `SizeOverflow` when width * height doesn't fit into usize
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    pub fn apply_lut_1d(&self, lut: &[Color32]) -> Result<Self, Error> {
+        if lut.is_empty() {
+            return Err(Error::DimensionMismatch);
+        }
+
+        let curve: Vec<[f32; 3]> = lut.iter().map(Color32::as_rgb).collect();
+        let max = (curve.len() - 1) as f32;
+        let lookup = |channel: usize, value: f32| {
+            let position = value.clamp(0.0, 1.0) * max;
+            let lower = position.floor() as usize;
+            let upper = (lower + 1).min(curve.len() - 1);
+            let t = position - lower as f32;
+
+            (curve[upper][channel] - curve[lower][channel]).mul_add(t, curve[lower][channel])
         };
 
-        match self.format.channels() {
-            4 => image::RgbaImage::from_vec(self.width, self.height, img_data)
-                .ok_or(Error::ConversionFailed("rgba image from f32 source data"))?
-                .save_with_format(path, image::ImageFormat::Png)
-                .map_err(|e| Error::EncodingFailed(e.to_string())),
-            3 => image::RgbImage::from_vec(self.width, self.height, img_data)
-                .ok_or(Error::ConversionFailed("rgb image from f32 source data"))?
-                .save_with_format(path, image::ImageFormat::Png)
-                .map_err(|e| Error::EncodingFailed(e.to_string())),
-            n => Err(Error::EncodingFailed(format!(
-                "channel count not supported ({n})",
-            ))),
-        }
+        self.map_rgb(|[r, g, b]| [lookup(0, r), lookup(1, g), lookup(2, b)])
     }
-}
 
-#[derive(Copy, Clone, Debug)]
-pub enum Pixel {
-    RgbaF32([f32; 4]),
-    RgbF32([f32; 3]),
-    RgbU8([u8; 3]),
-    RgbaU8([u8; 4]),
-}
+    /// Maps the colors through a 3D lookup table, trilinearly interpolated, for color grading.
+    /// Alpha stays the same.
+    ///
+    /// # Errors
+    /// `ConversionFailed` when either the width or the height can't be converted into usize
+    /// `SizeOverflow` when width * height doesn't fit into usize
+    pub fn apply_lut_3d(&self, lut: &Lut3D) -> Result<Self, Error> {
+        self.map_rgb(|rgb| lut.sample(rgb))
+    }
 
-impl PartialEq for Pixel {
-    fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (Self::RgbF32(s), Self::RgbF32(o)) => {
-                !s.iter().zip(o.iter()).any(|(s, o)| (s - o).abs() > EPSILON)
-            }
-            (Self::RgbaF32(s), Self::RgbaF32(o)) => {
-                !s.iter().zip(o.iter()).any(|(s, o)| (s - o).abs() > EPSILON)
-            }
+    /// Reduces the image to the colors of the palette, for example for retro or GIF style output.
+    ///
+    /// Every pixel is replaced by the nearest palette color, by euclidean distance of the linear
+    /// rgb channels. Alpha stays the same.
+    ///
+    /// # Errors
+    /// `DimensionMismatch` when the palette is empty
+    /// `ConversionFailed` when either the width or the height can't be converted into usize
+    /// `SizeOverflow` when width * height doesn't fit into usize
+    pub fn quantize(&self, palette: &[Color32]) -> Result<Self, Error> {
+        if palette.is_empty() {
+            return Err(Error::DimensionMismatch);
+        }
+
+        self.map_rgb(|[r, g, b]| {
+            Color32::from_rgb(r, g, b)
+                .nearest(palette)
+                .map_or([r, g, b], Color32::as_rgb)
+        })
+    }
+
+    /// Generates a palette of at most `colors` colors with the median cut algorithm and reduces
+    /// the image to it, see `quantize`.
+    ///
+    /// The pixels are repeatedly split at the median of the box with the widest channel range,
+    /// each palette color is the average of one box. Images with fewer distinct colors get a
+    /// smaller palette.
+    ///
+    /// # Errors
+    /// `DimensionMismatch` when colors is 0 or the image has no pixels
+    /// `ConversionFailed` when either the width or the height can't be converted into usize
+    /// `SizeOverflow` when width * height doesn't fit into usize
+    #[allow(clippy::cast_precision_loss)]
+    pub fn quantize_median_cut(&self, colors: usize) -> Result<(Vec<Color32>, Self), Error> {
+        if colors == 0 {
+            return Err(Error::DimensionMismatch);
+        }
+
+        let pixels = self.pixel_count()?;
+
+        //channel with the largest range of the box and that range
+        let widest_channel = |pixels: &[[f32; 3]]| -> (usize, f32) {
+            (0..3)
+                .map(|channel| {
+                    let (min, max) = pixels.iter().fold((f32::MAX, f32::MIN), |(min, max), rgb| {
+                        (min.min(rgb[channel]), max.max(rgb[channel]))
+                    });
+                    (channel, max - min)
+                })
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap_or((0, 0.0))
+        };
+
+        let pixels: Vec<[f32; 3]> = (0..pixels)
+            .map(|index| {
+                let [red, green, blue, _] = self.rgba_at(index);
+                [red, green, blue]
+            })
+            .collect();
+
+        let mut boxes = vec![pixels];
+        while boxes.len() < colors {
+            //boxes of a single color can't be split any further
+            let widest = boxes
+                .iter()
+                .enumerate()
+                .map(|(index, pixels)| (index, widest_channel(pixels)))
+                .filter(|(_, (_, range))| *range > 0.0)
+                .max_by(|a, b| a.1 .1.total_cmp(&b.1 .1));
+            let Some((index, (channel, _))) = widest else {
+                break;
+            };
+
+            let mut lower = boxes.swap_remove(index);
+            lower.sort_by(|a, b| a[channel].total_cmp(&b[channel]));
+            let upper = lower.split_off(lower.len() / 2);
+            boxes.push(lower);
+            boxes.push(upper);
+        }
+
+        let palette: Vec<Color32> = boxes
+            .iter()
+            .filter(|pixels| !pixels.is_empty())
+            .map(|pixels| {
+                let count = pixels.len() as f32;
+                let [red, green, blue] = pixels.iter().fold([0.0; 3], |sum, rgb| {
+                    [sum[0] + rgb[0], sum[1] + rgb[1], sum[2] + rgb[2]]
+                });
+                Color32::from_rgb(red / count, green / count, blue / count)
+            })
+            .collect();
+
+        let image = self.quantize(&palette)?;
+        Ok((palette, image))
+    }
+
+    /// Computes the mean, minimum, maximum and variance of each channel in a single pass, for
+    /// example for auto exposure or tolerant comparisons in tests.
+    ///
+    /// The statistics of an image without pixels are undefined, the mean would be NaN and the
+    /// minimum and maximum infinite, so it's an error instead.
+    ///
+    /// # Errors
+    /// `DimensionMismatch` when the image has no pixels
+    /// `ConversionFailed` when either the width or the height can't be converted into usize
+    /// `SizeOverflow` when width * height doesn't fit into usize
+    #[allow(clippy::cast_precision_loss)]
+    pub fn statistics(&self) -> Result<ImageStats, Error> {
+        let pixels = self.pixel_count()?;
+        if pixels == 0 {
+            return Err(Error::DimensionMismatch);
+        }
+
+        //Welford's algorithm, which stays accurate for large images unlike summing the squares
+        let mut stats = ImageStats {
+            mean: [0.0; 4],
+            min: [f32::INFINITY; 4],
+            max: [f32::NEG_INFINITY; 4],
+            variance: [0.0; 4],
+        };
+        for index in 0..pixels {
+            let count = (index + 1) as f32;
+            for (channel, value) in self.rgba_at(index).into_iter().enumerate() {
+                let delta = value - stats.mean[channel];
+                stats.mean[channel] += delta / count;
+                //summed squared differences, divided by the count at the end
+                stats.variance[channel] =
+                    delta.mul_add(value - stats.mean[channel], stats.variance[channel]);
+                stats.min[channel] = stats.min[channel].min(value);
+                stats.max[channel] = stats.max[channel].max(value);
+            }
+        }
+
+        let count = pixels as f32;
+        stats.variance = stats.variance.map(|squares| squares / count);
+        Ok(stats)
+    }
+
+    /// Finds the most common color of the image, for example to pick an accent color from cover
+    /// art.
+    ///
+    /// The pixels are sorted into a coarse grid of 4x4x4 rgb buckets, the result is the average
+    /// color, including alpha, of the fullest bucket.
+    ///
+    /// # Errors
+    /// `DimensionMismatch` when the image has no pixels
+    /// `ConversionFailed` when either the width or the height can't be converted into usize
+    /// `SizeOverflow` when width * height doesn't fit into usize
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    pub fn dominant_color(&self) -> Result<Color32, Error> {
+        const BUCKETS: usize = 4;
+
+        let pixels = self.pixel_count()?;
+
+        let bucket =
+            |channel: f32| ((channel.clamp(0.0, 1.0) * BUCKETS as f32) as usize).min(BUCKETS - 1);
+
+        //pixel count and sum of the rgba channels of each bucket
+        let mut histogram = vec![(0_usize, [0.0_f32; 4]); BUCKETS * BUCKETS * BUCKETS];
+        for index in 0..pixels {
+            let rgba = self.rgba_at(index);
+            let [r, g, b, _] = rgba.map(bucket);
+            let (count, sum) = &mut histogram[(r * BUCKETS + g) * BUCKETS + b];
+
+            *count += 1;
+            sum.iter_mut().zip(rgba).for_each(|(sum, c)| *sum += c);
+        }
+
+        let (count, [r, g, b, a]) = histogram
+            .into_iter()
+            .max_by_key(|(count, _)| *count)
+            .filter(|(count, _)| *count > 0)
+            .ok_or(Error::DimensionMismatch)?;
+        let count = count as f32;
+
+        Ok(Color32::from_rgba(
+            r / count,
+            g / count,
+            b / count,
+            a / count,
+        ))
+    }
+
+    /// Creates a copy of the image with the rgb channels of every pixel mapped by the function.
+    fn map_rgb(&self, f: impl Fn([f32; 3]) -> [f32; 3]) -> Result<Self, Error> {
+        let pixels = self.pixel_count()?;
+
+        let mut image = self.clone();
+        for index in 0..pixels {
+            let [red, green, blue, alpha] = self.rgba_at(index);
+            let [red, green, blue] = f([red, green, blue]);
+            image.set_rgba_at(index, [red, green, blue, alpha]);
+        }
+
+        Ok(image)
+    }
+
+    /// Creates a mask from the key color, like a green screen.
+    ///
+    /// Returns a rgba image, with the same channel type, in which all pixels whose rgb channels
+    /// differ by at most the tolerance from the key are transparent. All other pixels keep their
+    /// color and become opaque. Channels are compared as normalized values(0.0 - 1.0).
+    ///
+    /// # Errors
+    /// `ConversionFailed` when either the width or the height can't be converted into usize
+    /// `SizeOverflow` when width * height doesn't fit into usize
+    pub fn chroma_key(&self, key: Color32, tolerance: f32) -> Result<Self, Error> {
+        let format = match self.format {
+            Format::RgbU8 | Format::RgbaU8 | Format::RedU8 => Format::RgbaU8,
+            Format::RgbF32 | Format::RgbaF32 | Format::RedF32 => Format::RgbaF32,
+        };
+        let key = key.as_rgb();
+
+        let pixels = self.pixel_count()?;
+
+        let mut mask = Self {
+            width: self.width,
+            height: self.height,
+            format,
+            data: format.create_storage(pixels),
+        };
+
+        for index in 0..pixels {
+            let [r, g, b, _] = self.rgba_at(index);
+            let keyed = [r, g, b]
+                .iter()
+                .zip(key)
+                .all(|(c, k)| (c - k).abs() <= tolerance);
+
+            mask.set_rgba_at(index, [r, g, b, if keyed { 0.0 } else { 1.0 }]);
+        }
+
+        Ok(mask)
+    }
+
+    /// Composites the top image over this one with the blend mode, on the CPU.
+    ///
+    /// The blended color is mixed in by the alpha of the top image and the alphas are combined
+    /// like layers, the top one covering the bottom one. Channels are blended as normalized
+    /// values(0.0 - 1.0).
+    ///
+    /// # Errors
+    /// `DimensionMismatch` when the images don't have the same width, height and format
+    /// `ConversionFailed` when either the width or the height can't be converted into usize
+    /// `SizeOverflow` when width * height doesn't fit into usize
+    pub fn blend(&self, top: &Self, mode: BlendMode) -> Result<Self, Error> {
+        if self.width != top.width || self.height != top.height || self.format != top.format {
+            return Err(Error::DimensionMismatch);
+        }
+
+        let pixels = self.pixel_count()?;
+
+        let mut image = self.clone();
+        for index in 0..pixels {
+            let bottom = self.rgba_at(index);
+            let top = top.rgba_at(index);
+            let coverage = top[3];
+
+            let mut rgba = [0.0; 4];
+            for channel in 0..3 {
+                let blended = mode.apply(bottom[channel], top[channel]);
+                rgba[channel] = (blended - bottom[channel]).mul_add(coverage, bottom[channel]);
+            }
+            rgba[3] = bottom[3].mul_add(1.0 - coverage, coverage);
+
+            image.set_rgba_at(index, rgba);
+        }
+
+        Ok(image)
+    }
+
+    /// Shrinks the mask by taking the minimum over the square neighborhood of each pixel, for
+    /// example to remove speckles after `chroma_key`.
+    ///
+    /// Only the alpha channel is filtered, the color stays the same. Images without alpha channel
+    /// are treated as masks, with each channel filtered on its own.
+    ///
+    /// # Errors
+    /// `ConversionFailed` when either the width or the height can't be converted into usize
+    /// `SizeOverflow` when width * height doesn't fit into usize
+    pub fn erode(&self, radius: u32) -> Result<Self, Error> {
+        self.morphology(radius, f32::min)
+    }
+
+    /// Grows the mask by taking the maximum over the square neighborhood of each pixel, for
+    /// example to close holes after `chroma_key`. The inverse of `erode`, see there for details.
+    ///
+    /// # Errors
+    /// `ConversionFailed` when either the width or the height can't be converted into usize
+    /// `SizeOverflow` when width * height doesn't fit into usize
+    pub fn dilate(&self, radius: u32) -> Result<Self, Error> {
+        self.morphology(radius, f32::max)
+    }
+
+    /// Combines the masked channels over the square neighborhood, first along the rows and then
+    /// along the columns. The neighborhood is clamped to the edges of the image.
+    fn morphology(&self, radius: u32, combine: fn(f32, f32) -> f32) -> Result<Self, Error> {
+        let width: usize = self
+            .width
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("width to usize"))?;
+
+        let height: usize = self
+            .height
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("height to usize"))?;
+
+        let radius: usize = radius
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("radius to usize"))?;
+        let pixels = self.pixel_count()?;
+
+        let masked = |rgba: [f32; 4]| match self.format.channels() {
+            4 => [rgba[3]; 4],
+            _ => rgba,
+        };
+
+        let source: Vec<[f32; 4]> = (0..pixels)
+            .map(|index| masked(self.rgba_at(index)))
+            .collect();
+
+        let pass = |source: &[[f32; 4]], along_rows: bool| -> Vec<[f32; 4]> {
+            (0..pixels)
+                .map(|index| {
+                    let (x, y) = (index % width, index / width);
+                    let (position, length) = if along_rows { (x, width) } else { (y, height) };
+
+                    let start = position.saturating_sub(radius);
+                    let end = (position + radius).min(length - 1);
+                    (start..=end)
+                        .map(|i| {
+                            if along_rows {
+                                y * width + i
+                            } else {
+                                i * width + x
+                            }
+                        })
+                        .map(|neighbor| source[neighbor])
+                        .reduce(|a, b| [0, 1, 2, 3].map(|c| combine(a[c], b[c])))
+                        .unwrap_or(source[index])
+                })
+                .collect()
+        };
+        let filtered = pass(&pass(&source, true), false);
+
+        let mut image = self.clone();
+        for (index, mask) in filtered.into_iter().enumerate() {
+            let rgba = match self.format.channels() {
+                4 => {
+                    let [r, g, b, _] = self.rgba_at(index);
+                    [r, g, b, mask[3]]
+                }
+                _ => mask,
+            };
+            image.set_rgba_at(index, rgba);
+        }
+
+        Ok(image)
+    }
+
+    /// Saves the image as exr with 32 bit float channels.
+    fn save_exr(&self, path: &std::path::Path) -> Result<(), Error> {
+        let img_data: Vec<f32> = match &self.data {
+            Data::U8(data) => data.iter().map(|v| f32::from(*v) / 255.0).collect(),
+            Data::F32(data) => data.clone(),
+        };
+
+        let img = match self.format.channels() {
+            4 => image::Rgba32FImage::from_vec(self.width, self.height, img_data)
+                .ok_or(Error::ConversionFailed("rgba image from source data"))
+                .map(image::DynamicImage::ImageRgba32F)?,
+            3 => image::Rgb32FImage::from_vec(self.width, self.height, img_data)
+                .ok_or(Error::ConversionFailed("rgb image from source data"))
+                .map(image::DynamicImage::ImageRgb32F)?,
+            1 => image::Rgb32FImage::from_vec(
+                self.width,
+                self.height,
+                img_data.iter().flat_map(|v| [*v; 3]).collect(),
+            )
+            .ok_or(Error::ConversionFailed("gray image from source data"))
+            .map(image::DynamicImage::ImageRgb32F)?,
+            n => {
+                return Err(Error::EncodingFailed(format!(
+                    "channel count not supported ({n})",
+                )))
+            }
+        };
+
+        img.save_with_format(path, image::ImageFormat::OpenExr)
+            .map_err(|e| Error::EncodingFailed(e.to_string()))
+    }
+
+    /// Index of the pixel inside the data, without the channels.
+    const fn pixel_index(&self, x: u32, y: u32) -> usize {
+        y as usize * self.width as usize + x as usize
+    }
+
+    /// Number of pixels, width * height.
+    ///
+    /// # Errors
+    /// `ConversionFailed` when either the width or the height can't be converted into usize
+    /// `SizeOverflow` when the product doesn't fit into usize
+    fn pixel_count(&self) -> Result<usize, Error> {
+        let width: usize = self
+            .width
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("width to usize"))?;
+
+        let height: usize = self
+            .height
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("height to usize"))?;
+
+        width.checked_mul(height).ok_or(Error::SizeOverflow)
+    }
+
+    /// Reads the pixel at the index as normalized rgba values.
+    /// Formats without alpha channel are always opaque, single channel formats have no green
+    /// and blue.
+    fn rgba_at(&self, index: usize) -> [f32; 4] {
+        let channels: usize = self.format.channels().into();
+        let start = index * channels;
+
+        let mut rgba = if channels == 1 {
+            [0.0, 0.0, 0.0, 1.0]
+        } else {
+            [1.0; 4]
+        };
+        match &self.data {
+            Data::U8(data) => data[start..start + channels]
+                .iter()
+                .zip(rgba.iter_mut())
+                .for_each(|(v, c)| *c = f32::from(*v) / 255.0),
+            Data::F32(data) => data[start..start + channels]
+                .iter()
+                .zip(rgba.iter_mut())
+                .for_each(|(v, c)| *c = *v),
+        }
+        rgba
+    }
+
+    /// Writes normalized rgba values into the pixel at the index.
+    /// The alpha value is ignored for formats without alpha channel.
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    fn set_rgba_at(&mut self, index: usize, rgba: [f32; 4]) {
+        let channels: usize = self.format.channels().into();
+        let start = index * channels;
+
+        match &mut self.data {
+            Data::U8(data) => data[start..start + channels]
+                .iter_mut()
+                .zip(rgba)
+                .for_each(|(v, c)| *v = (c * 255.0).round().clamp(0.0, 255.0) as u8),
+            Data::F32(data) => data[start..start + channels]
+                .iter_mut()
+                .zip(rgba)
+                .for_each(|(v, c)| *v = c),
+        }
+    }
+
+    /// Constructor
+    /// Creates a new image from the raw bytes of an image file.
+    /// Currently, the png, jpeg, hdr and exr formats are supported.
+    /// Hdr and exr keep their values above 1.0 when loaded into a float format.
+    /// For raw pixel data, see `new` instead.
+    ///
+    /// # Errors
+    /// `FileNotFound` when the bytes don't match a valid file
+    pub fn load_from_memory(format: Format, data: &[u8]) -> Result<Self, Error> {
+        let img = match image::guess_format(data) {
+            Ok(file_format @ (image::ImageFormat::Hdr | image::ImageFormat::OpenExr)) => {
+                image::load_from_memory_with_format(data, file_format)
+            }
+            _ => image::load_from_memory(data),
+        };
+
+        match img {
+            Ok(img) => Self::from_dynamic_image(format, &img),
+            Err(e) => Err(Error::FileNotFound(e.to_string())),
+        }
+    }
+
+    /// Constructor
+    /// Creates a new image from an image file.
+    /// Currently, the png, jpeg, hdr(`.hdr`) and exr(`.exr`) formats are supported.
+    /// Hdr and exr keep their values above 1.0 when loaded into a float format.
+    ///
+    /// # Errors
+    /// `FileNotFound` when the file can't be found/loaded.
+    pub fn load_from_file(
+        format: Format,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(std::ffi::OsStr::to_str)
+            .map(str::to_ascii_lowercase);
+
+        let img = match extension.as_deref() {
+            Some(extension @ ("hdr" | "exr")) => {
+                let file_format = if extension == "hdr" {
+                    image::ImageFormat::Hdr
+                } else {
+                    image::ImageFormat::OpenExr
+                };
+
+                std::fs::File::open(path)
+                    .map_err(image::ImageError::IoError)
+                    .and_then(|file| image::load(std::io::BufReader::new(file), file_format))
+            }
+            _ => image::open(path),
+        };
+
+        match img {
+            Ok(img) => Self::from_dynamic_image(format, &img),
+            Err(e) => Err(Error::FileNotFound(e.to_string())),
+        }
+    }
+
+    /// Constructor
+    /// Creates a new image from a reader, for example a network stream or a file inside an
+    /// archive, without reading it into memory first.
+    /// The file format is guessed from the content, see `load_from_memory` for the supported
+    /// formats.
+    ///
+    /// # Errors
+    /// `FileNotFound` when the content can't be read or doesn't match a valid file
+    pub fn load_from_reader(
+        format: Format,
+        reader: impl std::io::Read + std::io::Seek,
+    ) -> Result<Self, Error> {
+        let img = image::ImageReader::new(std::io::BufReader::new(reader))
+            .with_guessed_format()
+            .map_err(image::ImageError::IoError)
+            .and_then(image::ImageReader::decode);
+
+        match img {
+            Ok(img) => Self::from_dynamic_image(format, &img),
+            Err(e) => Err(Error::FileNotFound(e.to_string())),
+        }
+    }
+
+    /// Converts a decoded image into the requested format.
+    /// Float sources(hdr, exr) are copied as they are, without clamping to the 0..1 range.
+    fn from_dynamic_image(format: Format, img: &image::DynamicImage) -> Result<Self, Error> {
+        let data = match (format, img) {
+            (Format::RgbU8, _) => Data::U8(img.to_rgb8().to_vec()),
+            (Format::RgbaU8, _) => Data::U8(img.to_rgba8().to_vec()),
+            (Format::RgbF32, image::DynamicImage::ImageRgb32F(buffer)) => {
+                Data::F32(buffer.to_vec())
+            }
+            (Format::RgbaF32, image::DynamicImage::ImageRgba32F(buffer)) => {
+                Data::F32(buffer.to_vec())
+            }
+            (Format::RgbF32, _) => Data::F32(img.to_rgb32f().to_vec()),
+            (Format::RgbaF32, _) => Data::F32(img.to_rgba32f().to_vec()),
+            //single channel images are loaded as luminance
+            (Format::RedU8, _) => Data::U8(img.to_luma8().to_vec()),
+            (Format::RedF32, _) => Data::F32(img.to_luma32f().to_vec()),
+        };
+
+        Self::new(img.width(), img.height(), format, data)
+    }
+
+    /// Saves the image to disk, in the file format matching the extension of the path.
+    ///
+    /// Supported are png(`.png`), jpeg(`.jpg` or `.jpeg`) and exr(`.exr`). Jpeg has no alpha
+    /// channel, so it gets discarded.
+    ///
+    /// Png and jpeg store 8 bits per channel, float values are clamped to 0.0 - 1.0. Exr stores
+    /// the values as 32 bit floats, without clamping, so that hdr images survive saving. Single
+    /// channel images are saved as gray rgb to exr.
+    ///
+    /// # Errors
+    /// `EncodingFailed` when the extension doesn't match one of the supported file formats or the
+    /// image couldn't be encoded into it.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn save_to_file(&self, path: impl AsRef<std::path::Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(std::ffi::OsStr::to_str)
+            .map(str::to_ascii_lowercase);
+
+        let file_format = match extension.as_deref() {
+            Some("exr") => return self.save_exr(path),
+            Some("png") => image::ImageFormat::Png,
+            Some("jpg" | "jpeg") => image::ImageFormat::Jpeg,
+            _ => {
+                return Err(Error::EncodingFailed(format!(
+                    "unsupported file extension ({})",
+                    path.display()
+                )))
+            }
+        };
+
+        let img_data = match &self.data {
+            Data::U8(data) => data.clone(),
+            Data::F32(data) => data
+                .iter()
+                .map(|v| ((255.0 * v.clamp(0.0, 255.0)) as u8))
+                .collect(),
+        };
+
+        let img = match self.format.channels() {
+            4 => image::RgbaImage::from_vec(self.width, self.height, img_data)
+                .ok_or(Error::ConversionFailed("rgba image from f32 source data"))
+                .map(image::DynamicImage::ImageRgba8)?,
+            3 => image::RgbImage::from_vec(self.width, self.height, img_data)
+                .ok_or(Error::ConversionFailed("rgb image from f32 source data"))
+                .map(image::DynamicImage::ImageRgb8)?,
+            1 => image::GrayImage::from_vec(self.width, self.height, img_data)
+                .ok_or(Error::ConversionFailed("gray image from f32 source data"))
+                .map(image::DynamicImage::ImageLuma8)?,
+            n => {
+                return Err(Error::EncodingFailed(format!(
+                    "channel count not supported ({n})",
+                )))
+            }
+        };
+
+        let img = if file_format == image::ImageFormat::Jpeg {
+            image::DynamicImage::ImageRgb8(img.to_rgb8())
+        } else {
+            img
+        };
+
+        img.save_with_format(path, file_format)
+            .map_err(|e| Error::EncodingFailed(e.to_string()))
+    }
+}
+
+/// Weights of the source pixels that contribute to the destination pixel, along one axis.
+///
+/// The Catmull-Rom kernel gets widened by the downscale factor, so that every source pixel
+/// contributes. Pixels outside the image are clamped to the border.
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn bicubic_weights(destination: u32, source_len: u32, destination_len: u32) -> Vec<(u32, f32)> {
+    fn catmull_rom(t: f32) -> f32 {
+        let t = t.abs();
+        if t < 1.0 {
+            (1.5f32.mul_add(t, -2.5) * t).mul_add(t, 1.0)
+        } else if t < 2.0 {
+            (-0.5f32).mul_add(t, 2.5).mul_add(t, -4.0).mul_add(t, 2.0)
+        } else {
+            0.0
+        }
+    }
+
+    let scale = destination_len as f32 / source_len as f32;
+    let support = 2.0 / scale;
+    let center = (destination as f32 + 0.5) / scale - 0.5;
+
+    let start = (center - support).floor() as i64;
+    let end = (center + support).ceil() as i64;
+    let last = i64::from(source_len) - 1;
+
+    let mut weights: Vec<(u32, f32)> = (start..=end)
+        .map(|i| {
+            let weight = catmull_rom((i as f32 - center) * scale);
+            (i.clamp(0, last) as u32, weight)
+        })
+        .filter(|(_, weight)| *weight != 0.0)
+        .collect();
+
+    let sum: f32 = weights.iter().map(|(_, weight)| weight).sum();
+    for (_, weight) in &mut weights {
+        *weight /= sum;
+    }
+
+    weights
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum Pixel {
+    RgbaF32([f32; 4]),
+    RgbF32([f32; 3]),
+    RgbU8([u8; 3]),
+    RgbaU8([u8; 4]),
+    RedU8(u8),
+    RedF32(f32),
+}
+
+impl PartialEq for Pixel {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::RgbF32(s), Self::RgbF32(o)) => {
+                !s.iter().zip(o.iter()).any(|(s, o)| (s - o).abs() > EPSILON)
+            }
+            (Self::RgbaF32(s), Self::RgbaF32(o)) => {
+                !s.iter().zip(o.iter()).any(|(s, o)| (s - o).abs() > EPSILON)
+            }
             (Self::RgbU8(s), Self::RgbU8(o)) => !s.iter().zip(o.iter()).any(|(s, o)| s != o),
             (Self::RgbaU8(s), Self::RgbaU8(o)) => !s.iter().zip(o.iter()).any(|(s, o)| s != o),
+            (Self::RedU8(s), Self::RedU8(o)) => s == o,
+            (Self::RedF32(s), Self::RedF32(o)) => (s - o).abs() <= EPSILON,
             _ => false,
         }
     }
@@ -395,17 +1605,38 @@ impl Format {
     #[must_use]
     pub const fn channels(&self) -> u8 {
         match self {
+            Self::RedU8 | Self::RedF32 => 1,
             Self::RgbU8 | Self::RgbF32 => 3,
             Self::RgbaU8 | Self::RgbaF32 => 4,
         }
     }
 
+    /// Number of values of an image with the dimensions, width * height * channels.
+    ///
+    /// # Errors
+    /// `ConversionFailed` when either the width or the height can't be converted into usize
+    /// `SizeOverflow` when the product doesn't fit into usize
+    pub fn data_len(&self, width: u32, height: u32) -> Result<usize, Error> {
+        let width: usize = width
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("width to usize"))?;
+
+        let height: usize = height
+            .try_into()
+            .map_err(|_| Error::ConversionFailed("height to usize"))?;
+
+        width
+            .checked_mul(height)
+            .and_then(|pixels| pixels.checked_mul(self.channels().into()))
+            .ok_or(Error::SizeOverflow)
+    }
+
     #[must_use]
     pub fn create_storage(&self, pixel_count: usize) -> Data {
         let c = self.channels() as usize;
         match self {
-            Self::RgbaU8 | Self::RgbU8 => Data::U8(vec![0; pixel_count * c]),
-            Self::RgbF32 | Self::RgbaF32 => Data::F32(vec![0.0; pixel_count * c]),
+            Self::RgbaU8 | Self::RgbU8 | Self::RedU8 => Data::U8(vec![0; pixel_count * c]),
+            Self::RgbF32 | Self::RgbaF32 | Self::RedF32 => Data::F32(vec![0.0; pixel_count * c]),
         }
     }
 }
@@ -415,6 +1646,7 @@ impl TryFrom<&[f32]> for Pixel {
 
     fn try_from(value: &[f32]) -> Result<Self, Self::Error> {
         match value.len() {
+            1 => Ok(Self::RedF32(value[0])),
             3 => Ok(Self::RgbF32([value[0], value[1], value[2]])),
             4 => Ok(Self::RgbaF32([value[0], value[1], value[2], value[3]])),
             _ => Err(Error::ConversionFailed(
@@ -429,6 +1661,7 @@ impl TryFrom<&[u8]> for Pixel {
 
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
         match value.len() {
+            1 => Ok(Self::RedU8(value[0])),
             3 => Ok(Self::RgbU8([value[0], value[1], value[2]])),
             4 => Ok(Self::RgbaU8([value[0], value[1], value[2], value[3]])),
             _ => Err(Error::ConversionFailed(
@@ -458,6 +1691,8 @@ mod test {
             Format::RgbF32 => Data::F32(img.to_rgb32f().to_vec()),
             Format::RgbaU8 => Data::U8(img.to_rgba8().to_vec()),
             Format::RgbaF32 => Data::F32(img.to_rgba32f().to_vec()),
+            Format::RedU8 => Data::U8(img.to_luma8().to_vec()),
+            Format::RedF32 => Data::F32(img.to_luma32f().to_vec()),
         };
         let image = Image::new(img.width(), img.height(), format, data).unwrap();
 
@@ -512,4 +1747,656 @@ mod test {
         hash_test_format(Format::RgbU8);
         hash_test_format(Format::RgbaU8);
     }
+
+    #[test]
+    fn save_format_from_extension_test() {
+        let image = Image::with_color32(4, 4, Color32::RED, Format::RgbaU8).unwrap();
+        let dir = std::env::temp_dir();
+
+        for (name, magic) in [
+            ("cac_save_test.png", &[0x89, b'P', b'N', b'G'][..]),
+            ("cac_save_test.jpg", &[0xFF, 0xD8][..]),
+            ("cac_save_test.JPEG", &[0xFF, 0xD8][..]),
+        ] {
+            let path = dir.join(name);
+            image.save_to_file(&path).unwrap();
+
+            let bytes = std::fs::read(&path).unwrap();
+            assert!(bytes.starts_with(magic), "{name} has the wrong format");
+
+            let loaded = Image::load_from_file(Format::RgbaU8, &path).unwrap();
+            assert_eq!((loaded.width, loaded.height), (4, 4));
+
+            std::fs::remove_file(path).unwrap();
+        }
+
+        let result = image.save_to_file(dir.join("cac_save_test.unknown"));
+        assert!(matches!(result, Err(Error::EncodingFailed(_))));
+    }
+
+    #[test]
+    fn load_from_reader_test() {
+        let data = include_bytes!("../res/ferris_small.png");
+
+        for format in [Format::RgbU8, Format::RgbaF32] {
+            let from_reader = Image::load_from_reader(format, std::io::Cursor::new(data)).unwrap();
+            let from_memory = Image::load_from_memory(format, data).unwrap();
+
+            assert_eq!(from_reader.width, from_memory.width);
+            assert_eq!(from_reader.height, from_memory.height);
+            assert_eq!(from_reader.data, from_memory.data);
+        }
+
+        let invalid = Image::load_from_reader(Format::RgbU8, std::io::Cursor::new([0_u8; 16]));
+        assert!(matches!(invalid, Err(Error::FileNotFound(_))));
+    }
+
+    #[test]
+    fn load_hdr_keeps_values_above_one() {
+        let (width, height) = (4, 2);
+        let pixels = vec![image::Rgb([2.5_f32, 1.0, 0.25]); width * height];
+
+        let mut encoded = Vec::new();
+        image::codecs::hdr::HdrEncoder::new(&mut encoded)
+            .encode(&pixels, width, height)
+            .unwrap();
+
+        let img = Image::load_from_memory(Format::RgbF32, &encoded).unwrap();
+        let Some(Pixel::RgbF32([r, g, b])) = img.sample(1, 1) else {
+            panic!("expected a RgbF32 pixel");
+        };
+
+        assert!((r - 2.5).abs() < 0.05);
+        assert!((g - 1.0).abs() < 0.05);
+        assert!((b - 0.25).abs() < 0.05);
+
+        let path = std::env::temp_dir().join("cac_core_load_hdr_test.hdr");
+        std::fs::write(&path, &encoded).unwrap();
+        let from_file = Image::load_from_file(Format::RgbaF32, &path);
+        std::fs::remove_file(&path).unwrap();
+
+        let Some(Pixel::RgbaF32([r, ..])) = from_file.unwrap().sample(0, 0) else {
+            panic!("expected a RgbaF32 pixel");
+        };
+        assert!(r > 1.0);
+    }
+
+    #[test]
+    fn save_exr_keeps_values_above_one() {
+        let img = Image::from_fn(4, 2, Format::RgbaF32, |x, _| {
+            Color32::from_rgba(2.5, 1.0, 0.25, if x == 0 { 0.5 } else { 1.0 })
+        })
+        .unwrap();
+
+        let path = std::env::temp_dir().join("cac_core_save_exr_test.exr");
+        img.save_to_file(&path).unwrap();
+        let loaded = Image::load_from_file(Format::RgbaF32, &path);
+        std::fs::remove_file(&path).unwrap();
+
+        //exr stores 32 bit floats, so the values are exactly the same
+        assert!(loaded.unwrap() == img);
+
+        let red = Image::with_color32(2, 2, Color32::RED, Format::RedF32).unwrap();
+        let path = std::env::temp_dir().join("cac_core_save_exr_red_test.exr");
+        red.save_to_file(&path).unwrap();
+        let loaded = Image::load_from_file(Format::RgbF32, &path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.unwrap().sample(1, 1), Some(Pixel::RgbF32([1.0; 3])));
+    }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn bicubic_resize_test() {
+        //one pixel wide black and white stripes, which should average out to grey
+        let stripes = Image::from_fn(64, 64, Format::RgbaF32, |x, _| {
+            if x % 2 == 0 {
+                Color32::WHITE
+            } else {
+                Color32::BLACK
+            }
+        })
+        .unwrap();
+
+        let average_error = |img: &Image| {
+            let mut error = 0.0;
+            for y in 0..img.height {
+                for x in 0..img.width {
+                    let Some(Pixel::RgbaF32([r, ..])) = img.sample(x, y) else {
+                        panic!("expected a RgbaF32 pixel");
+                    };
+                    error += (r - 0.5).abs();
+                }
+            }
+            error / (img.width * img.height) as f32
+        };
+
+        let nearest = stripes.resize_with(21, 21, Filter::Nearest).unwrap();
+        let bicubic = stripes.resize_with(21, 21, Filter::Bicubic).unwrap();
+        assert!(average_error(&bicubic) < average_error(&nearest) * 0.5);
+
+        //uniform colors stay the same
+        let red = Image::with_color32(17, 9, Color32::RED, Format::RgbU8).unwrap();
+        let resized = red.resize_with(5, 3, Filter::Bicubic).unwrap();
+        assert_eq!(
+            resized.data,
+            Image::with_color32(5, 3, Color32::RED, Format::RgbU8)
+                .unwrap()
+                .data
+        );
+    }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn chroma_key_test() {
+        //green screen on the left half, with a slight noise
+        let img = Image::from_fn(8, 4, Format::RgbU8, |x, y| {
+            if x < 4 {
+                Color32::from_rgb(0.02 * (y as f32), 1.0, 0.0)
+            } else {
+                Color32::PERSIAN_INDIGO
+            }
+        })
+        .unwrap();
+
+        let mask = img.chroma_key(Color32::GREEN, 0.1).unwrap();
+        assert_eq!(mask.format, Format::RgbaU8);
+
+        for y in 0..mask.height {
+            for x in 0..mask.width {
+                let Some(Pixel::RgbaU8([.., a])) = mask.sample(x, y) else {
+                    panic!("expected a RgbaU8 pixel");
+                };
+                assert_eq!(a, if x < 4 { 0 } else { 255 });
+            }
+        }
+    }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn lut_test() {
+        let img = Image::from_fn(16, 16, Format::RgbaU8, |x, y| {
+            Color32::from_rgba(x as f32 / 15.0, y as f32 / 15.0, 0.3, 0.5)
+        })
+        .unwrap();
+
+        let identity = img.apply_lut_3d(&Lut3D::identity(5).unwrap()).unwrap();
+        assert_eq!(identity.data, img.data);
+
+        let inverted = img.apply_lut_1d(&[Color32::WHITE, Color32::BLACK]).unwrap();
+        for (x, y) in [(0, 0), (3, 7), (15, 15)] {
+            let (Some(Pixel::RgbaU8(original)), Some(Pixel::RgbaU8(inverted))) =
+                (img.sample(x, y), inverted.sample(x, y))
+            else {
+                panic!("expected RgbaU8 pixels");
+            };
+
+            for channel in 0..3 {
+                assert_eq!(inverted[channel], 255 - original[channel]);
+            }
+            assert_eq!(inverted[3], original[3]);
+        }
+
+        assert!(Lut3D::new(3, vec![Color32::BLACK; 26]).is_err());
+        //rejected before any color is generated
+        assert!(Lut3D::identity(1 << 22).is_err());
+        assert!(Lut3D::identity(1).is_err());
+        assert!(img.apply_lut_1d(&[]).is_err());
+    }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn quantize_test() {
+        //horizontal gradient from green to red
+        let img = Image::from_fn(64, 8, Format::RgbU8, |x, _| {
+            let t = x as f32 / 63.0;
+            Color32::from_rgb(t, 1.0 - t, 0.5)
+        })
+        .unwrap();
+
+        let (palette, quantized) = img.quantize_median_cut(4).unwrap();
+        assert_eq!(palette.len(), 4);
+
+        let expected: Vec<Pixel> = palette
+            .iter()
+            .map(|color| {
+                let [r, g, b, _] = color.as_rgba_u8();
+                Pixel::RgbU8([r, g, b])
+            })
+            .collect();
+        let mut used = Vec::new();
+        for y in 0..8 {
+            for x in 0..64 {
+                let pixel = quantized.sample(x, y).unwrap();
+                assert!(expected.contains(&pixel), "{pixel:?} is not in the palette");
+                if !used.contains(&pixel) {
+                    used.push(pixel);
+                }
+            }
+        }
+        assert_eq!(used.len(), 4);
+
+        let two_colors = img.quantize(&[Color32::RED, Color32::GREEN]).unwrap();
+        assert_eq!(two_colors.sample(0, 0), Some(Pixel::RgbU8([0, 255, 0])));
+        assert_eq!(two_colors.sample(63, 0), Some(Pixel::RgbU8([255, 0, 0])));
+
+        //a single color can't be split into more
+        let flat = Image::with_color32(4, 4, Color32::RED, Format::RgbU8).unwrap();
+        let (palette, _) = flat.quantize_median_cut(8).unwrap();
+        assert_eq!(palette.len(), 1);
+
+        assert!(img.quantize(&[]).is_err());
+        assert!(img.quantize_median_cut(0).is_err());
+    }
+
+    #[test]
+    fn dominant_color_test() {
+        //mostly red, with a column of blue
+        let img = Image::from_fn(10, 10, Format::RgbU8, |x, y| {
+            if x == 9 {
+                Color32::BLUE
+            } else if y % 2 == 0 {
+                Color32::from_rgb(0.9, 0.1, 0.0)
+            } else {
+                Color32::RED
+            }
+        })
+        .unwrap();
+
+        let [r, g, b, a] = img.dominant_color().unwrap().as_rgba();
+        assert!((r - 0.95).abs() < 0.01);
+        assert!((g - 0.05).abs() < 0.01);
+        assert!(b.abs() < 0.01);
+        assert!((a - 1.0).abs() < 0.01);
+
+        let empty = Image::from_fn(0, 0, Format::RgbU8, |_, _| Color32::RED).unwrap();
+        assert!(empty.dominant_color().is_err());
+    }
+
+    #[test]
+    fn sample_subpixel_test() {
+        let colors = [Color32::RED, Color32::GREEN, Color32::BLUE, Color32::WHITE];
+        let img =
+            Image::from_fn(2, 2, Format::RgbaF32, |x, y| colors[(y * 2 + x) as usize]).unwrap();
+
+        let close = |color: Option<Color32>, expected: [f32; 4]| {
+            color.is_some_and(|color| {
+                color
+                    .as_rgba()
+                    .iter()
+                    .zip(expected)
+                    .all(|(c, e)| (c - e).abs() < 0.001)
+            })
+        };
+
+        //the average of all four
+        assert!(close(img.sample_subpixel(0.5, 0.5), [0.5, 0.5, 0.5, 1.0]));
+        //exactly on a pixel center
+        assert!(close(img.sample_subpixel(1.0, 0.0), [0.0, 1.0, 0.0, 1.0]));
+        //between the top two
+        assert!(close(
+            img.sample_subpixel(0.25, 0.0),
+            [0.75, 0.25, 0.0, 1.0]
+        ));
+
+        assert!(img.sample_subpixel(1.5, 0.0).is_none());
+        assert!(img.sample_subpixel(0.0, -0.1).is_none());
+        assert!(img.sample_subpixel(f32::NAN, 0.0).is_none());
+    }
+
+    #[test]
+    fn draw_grid_test() {
+        let mut img = Image::with_color32(8, 8, Color32::BLACK, Format::RgbU8).unwrap();
+        img.draw_grid(4, Color32::WHITE);
+
+        let is_white =
+            |img: &Image, x, y| matches!(img.sample(x, y), Some(Pixel::RgbU8([255, 255, 255])));
+        for i in 0..8 {
+            assert!(is_white(&img, 0, i));
+            assert!(is_white(&img, 4, i));
+            assert!(is_white(&img, i, 0));
+            assert!(is_white(&img, i, 4));
+        }
+
+        assert!(!is_white(&img, 1, 1));
+        assert!(!is_white(&img, 3, 7));
+        assert!(!is_white(&img, 7, 5));
+
+        //no lines at all
+        img.draw_grid(0, Color32::RED);
+        assert!(is_white(&img, 0, 0));
+        assert!(!is_white(&img, 1, 1));
+    }
+
+    #[test]
+    fn rotate_test() {
+        let square = Image::with_color32(8, 8, Color32::RED, Format::RgbaF32).unwrap();
+        let rotated = square.rotate(45.0, Color32::BLUE).unwrap();
+
+        //the diagonal of the square is 8 * sqrt(2)
+        assert_eq!((rotated.width, rotated.height), (12, 12));
+
+        let color = |image: &Image, x, y| image.rgba_at(image.pixel_index(x, y));
+        let close = |a: [f32; 4], b: [f32; 4]| a.iter().zip(b).all(|(a, b)| (a - b).abs() < 0.01);
+        assert!(close(color(&rotated, 6, 6), Color32::RED.as_rgba()));
+        for (x, y) in [(0, 0), (11, 0), (0, 11), (11, 11)] {
+            assert!(close(color(&rotated, x, y), Color32::BLUE.as_rgba()));
+        }
+
+        //a quarter turn swaps the sides and moves the top left pixel to the top right
+        let wide = Image::from_fn(4, 2, Format::RgbaF32, |x, y| {
+            if (x, y) == (0, 0) {
+                Color32::GREEN
+            } else {
+                Color32::RED
+            }
+        })
+        .unwrap();
+        let turned = wide.rotate(90.0, Color32::BLUE).unwrap();
+        assert_eq!((turned.width, turned.height), (2, 4));
+        assert!(close(color(&turned, 1, 0), Color32::GREEN.as_rgba()));
+        assert!(close(color(&turned, 0, 3), Color32::RED.as_rgba()));
+    }
+
+    #[test]
+    fn statistics_test() {
+        //half black, half orange
+        let img = Image::from_fn(4, 2, Format::RgbF32, |x, _| {
+            if x < 2 {
+                Color32::BLACK
+            } else {
+                Color32::from_rgb(1.0, 0.5, 0.0)
+            }
+        })
+        .unwrap();
+
+        let stats = img.statistics().unwrap();
+        let close = |a: [f32; 4], b: [f32; 4]| a.iter().zip(b).all(|(a, b)| (a - b).abs() < 0.0001);
+
+        assert!(close(stats.mean, [0.5, 0.25, 0.0, 1.0]));
+        assert!(close(stats.min, [0.0, 0.0, 0.0, 1.0]));
+        assert!(close(stats.max, [1.0, 0.5, 0.0, 1.0]));
+        //each value is half the range away from the mean
+        assert!(close(stats.variance, [0.25, 0.0625, 0.0, 0.0]));
+
+        //the u8 values are normalized
+        let img = Image::from_fn(2, 1, Format::RgbaU8, |x, _| {
+            if x == 0 {
+                Color32::from_rgba(0.0, 0.0, 0.0, 0.0)
+            } else {
+                Color32::WHITE
+            }
+        })
+        .unwrap();
+        let stats = img.statistics().unwrap();
+        assert!(close(stats.mean, [0.5; 4]));
+        assert!(close(stats.min, [0.0; 4]));
+        assert!(close(stats.max, [1.0; 4]));
+        assert!(close(stats.variance, [0.25; 4]));
+
+        //red only images have no green and blue, and are opaque
+        let img = Image::from_fn(2, 2, Format::RedU8, |x, _| {
+            if x == 0 {
+                Color32::BLACK
+            } else {
+                Color32::RED
+            }
+        })
+        .unwrap();
+        let stats = img.statistics().unwrap();
+        assert!(close(stats.mean, [0.5, 0.0, 0.0, 1.0]));
+        assert!(close(stats.min, [0.0, 0.0, 0.0, 1.0]));
+        assert!(close(stats.max, [1.0, 0.0, 0.0, 1.0]));
+        assert!(close(stats.variance, [0.25, 0.0, 0.0, 0.0]));
+
+        let empty = Image::with_color32(0, 0, Color32::BLACK, Format::RgbF32).unwrap();
+        assert!(matches!(empty.statistics(), Err(Error::DimensionMismatch)));
+    }
+
+    #[test]
+    fn concat_test() {
+        let red = Image::with_color32(4, 4, Color32::RED, Format::RgbU8).unwrap();
+        let blue = Image::with_color32(4, 4, Color32::BLUE, Format::RgbU8).unwrap();
+
+        let is = |image: &Image, x, y, color: [u8; 3]| matches!(image.sample(x, y), Some(Pixel::RgbU8(pixel)) if pixel == color);
+
+        let side_by_side = Image::concat_horizontal(&[&red, &blue]).unwrap();
+        assert_eq!((side_by_side.width, side_by_side.height), (8, 4));
+        for y in 0..4 {
+            assert!(is(&side_by_side, 3, y, [255, 0, 0]));
+            assert!(is(&side_by_side, 4, y, [0, 0, 255]));
+        }
+
+        let stacked = Image::concat_vertical(&[&red, &blue, &red]).unwrap();
+        assert_eq!((stacked.width, stacked.height), (4, 12));
+        assert!(is(&stacked, 0, 3, [255, 0, 0]));
+        assert!(is(&stacked, 3, 4, [0, 0, 255]));
+        assert!(is(&stacked, 2, 8, [255, 0, 0]));
+
+        let small = Image::with_color32(4, 2, Color32::RED, Format::RgbU8).unwrap();
+        let float = Image::with_color32(4, 4, Color32::RED, Format::RgbF32).unwrap();
+        assert!(Image::concat_horizontal(&[&red, &small]).is_err());
+        assert!(Image::concat_vertical(&[&red, &small]).is_ok());
+        assert!(Image::concat_vertical(&[&red, &float]).is_err());
+        assert!(Image::concat_horizontal(&[]).is_err());
+    }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn downsample_2x_test() {
+        let img = Image::from_fn(4, 4, Format::RgbaF32, |x, y| {
+            let value = (y * 4 + x) as f32 / 16.0;
+            Color32::from_rgba(value, 1.0 - value, 0.0, 1.0)
+        })
+        .unwrap();
+
+        let half = img.downsample_2x().unwrap();
+        assert_eq!((half.width, half.height), (2, 2));
+
+        for y in 0..2 {
+            for x in 0..2 {
+                let mut expected = [0.0; 4];
+                for (column, row) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let source = img.rgba_at(img.pixel_index(x * 2 + column, y * 2 + row));
+                    expected
+                        .iter_mut()
+                        .zip(source)
+                        .for_each(|(e, s)| *e += s / 4.0);
+                }
+
+                let actual = half.rgba_at(half.pixel_index(x, y));
+                assert!(actual
+                    .iter()
+                    .zip(expected)
+                    .all(|(a, e)| (a - e).abs() < 0.0001));
+            }
+        }
+
+        //the last column is averaged with itself
+        let odd = Image::from_fn(3, 1, Format::RedF32, |x, _| {
+            Color32::from_rgb(x as f32, 0.0, 0.0)
+        })
+        .unwrap()
+        .downsample_2x()
+        .unwrap();
+        assert_eq!((odd.width, odd.height), (2, 1));
+        assert!((odd.rgba_at(0)[0] - 0.5).abs() < f32::EPSILON);
+        assert!((odd.rgba_at(1)[0] - 2.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn morphology_test() {
+        //opaque mask with a transparent speck in the center
+        let img = Image::from_fn(9, 9, Format::RgbaU8, |x, y| {
+            let alpha = if (x, y) == (4, 4) { 0.0 } else { 1.0 };
+            Color32::from_rgba(0.2, 0.4, 0.6, alpha)
+        })
+        .unwrap();
+        let alpha = |img: &Image, x, y| match img.sample(x, y) {
+            Some(Pixel::RgbaU8([.., a])) => a,
+            pixel => panic!("unexpected pixel {pixel:?}"),
+        };
+
+        let eroded = img.erode(1).unwrap();
+        assert_eq!(alpha(&eroded, 3, 3), 0);
+        assert_eq!(alpha(&eroded, 5, 5), 0);
+        assert_eq!(alpha(&eroded, 2, 2), 255);
+        assert_eq!(eroded.sample(3, 3), Some(Pixel::RgbaU8([51, 102, 153, 0])));
+
+        //closing the hole
+        let closed = img.dilate(1).unwrap().erode(1).unwrap();
+        assert_eq!(closed.data, img.dilate(1).unwrap().data);
+        assert!((0..9).all(|y| (0..9).all(|x| alpha(&closed, x, y) == 255)));
+
+        assert_eq!(img.erode(0).unwrap().data, img.data);
+    }
+
+    #[test]
+    fn size_overflow_test() {
+        let overflow = Image::new(u32::MAX, u32::MAX, Format::RgbaU8, Data::U8(Vec::new()));
+        assert!(matches!(overflow, Err(Error::SizeOverflow)));
+
+        let overflow = Image::with_color32(u32::MAX, u32::MAX, Color32::RED, Format::RgbU8);
+        assert!(matches!(overflow, Err(Error::SizeOverflow)));
+
+        let short = Image::new(2, 2, Format::RgbU8, Data::U8(vec![0; 11]));
+        assert!(matches!(short, Err(Error::DimensionMismatch)));
+    }
+
+    #[test]
+    fn red_format_test() {
+        let img =
+            Image::with_color32(3, 2, Color32::from_rgb(0.2, 0.4, 0.6), Format::RedU8).unwrap();
+        assert_eq!(img.data.len(), 6);
+        assert_eq!(img.sample(2, 1), Some(Pixel::RedU8(51)));
+        let rgba = img.rgba_at(0);
+        let expected = [0.2, 0.0, 0.0, 1.0];
+        assert!(rgba
+            .iter()
+            .zip(expected)
+            .all(|(c, e)| (c - e).abs() < EPSILON));
+
+        let keyed = img.chroma_key(Color32::BLACK, 0.1).unwrap();
+        assert_eq!(keyed.sample(0, 0), Some(Pixel::RgbaU8([51, 0, 0, 255])));
+    }
+
+    #[test]
+    fn blend_test() {
+        let color = Color32::from_rgb(0.2, 0.6, 1.0);
+        let base = Image::with_color32(4, 4, color, Format::RgbaF32).unwrap();
+        let white = Image::with_color32(4, 4, Color32::WHITE, Format::RgbaF32).unwrap();
+        let black = Image::with_color32(4, 4, Color32::BLACK, Format::RgbaF32).unwrap();
+        let rgba = |img: &Image| img.rgba_at(img.pixel_index(1, 2));
+        let close = |a: [f32; 4], b: [f32; 4]| a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-5);
+
+        let multiplied = base.blend(&white, BlendMode::Multiply).unwrap();
+        assert!(close(rgba(&multiplied), color.as_rgba()));
+
+        let multiplied = base.blend(&black, BlendMode::Multiply).unwrap();
+        assert!(close(rgba(&multiplied), [0.0, 0.0, 0.0, 1.0]));
+
+        let screened = base.blend(&black, BlendMode::Screen).unwrap();
+        assert!(close(rgba(&screened), color.as_rgba()));
+
+        let overlay = base.blend(&white, BlendMode::Overlay).unwrap();
+        assert!(close(rgba(&overlay), [0.4, 1.0, 1.0, 1.0]));
+
+        let added = base.blend(&base, BlendMode::Add).unwrap();
+        assert!(close(rgba(&added), [0.4, 1.0, 1.0, 1.0]));
+
+        //a transparent top layer keeps the bottom one
+        let clear = Image::with_color32(
+            4,
+            4,
+            Color32::from_rgba(1.0, 0.0, 0.0, 0.0),
+            Format::RgbaF32,
+        )
+        .unwrap();
+        let normal = base.blend(&clear, BlendMode::Normal).unwrap();
+        assert!(close(rgba(&normal), color.as_rgba()));
+
+        let small = Image::with_color32(2, 2, color, Format::RgbaF32).unwrap();
+        assert!(matches!(
+            base.blend(&small, BlendMode::Normal),
+            Err(Error::DimensionMismatch)
+        ));
+    }
+
+    #[test]
+    fn resize_premultiplied_test() {
+        //white on the left, transparent black on the right
+        let sprite = Image::from_fn(8, 8, Format::RgbaU8, |x, _| {
+            if x < 4 {
+                Color32::WHITE
+            } else {
+                Color32::from_rgba(0.0, 0.0, 0.0, 0.0)
+            }
+        })
+        .unwrap();
+
+        let edge = |img: &Image| match img.sample(1, 1) {
+            Some(Pixel::RgbaU8(rgba)) => rgba,
+            pixel => panic!("unexpected pixel {pixel:?}"),
+        };
+
+        let premultiplied = sprite.resize_premultiplied(3, 3, Filter::Bicubic).unwrap();
+
+        //the edge is partially covered, but keeps the white of the covered pixels
+        let [r, g, b, a] = edge(&premultiplied);
+        assert!(a > 0 && a < 255);
+        assert!(r >= 254 && g >= 254 && b >= 254);
+
+        //the coverage matches the straight resize, only the color isn't weighted by it
+        let straight = sprite.resize_with(3, 3, Filter::Bicubic).unwrap();
+        assert_eq!(edge(&straight)[3], a);
+
+        //the fully covered and the fully transparent side are unchanged
+        assert_eq!(
+            premultiplied.sample(0, 1),
+            Some(Pixel::RgbaU8([255, 255, 255, 255]))
+        );
+        assert_eq!(
+            premultiplied.sample(2, 1),
+            Some(Pixel::RgbaU8([0, 0, 0, 0]))
+        );
+    }
+
+    #[test]
+    fn from_fn_test() {
+        #[allow(clippy::cast_precision_loss)]
+        let uv = |x: u32, y: u32| Color32::from_rgb(x as f32 / 3.0, y as f32 / 3.0, 0.0);
+
+        let image = Image::from_fn(4, 4, Format::RgbaU8, uv).unwrap();
+
+        assert_eq!(image.sample(0, 0), Some(Pixel::RgbaU8([0, 0, 0, 255])));
+        assert_eq!(image.sample(3, 0), Some(Pixel::RgbaU8([255, 0, 0, 255])));
+        assert_eq!(image.sample(0, 3), Some(Pixel::RgbaU8([0, 255, 0, 255])));
+        assert_eq!(image.sample(3, 3), Some(Pixel::RgbaU8([255, 255, 0, 255])));
+
+        let image = Image::from_fn(4, 4, Format::RgbF32, uv).unwrap();
+        assert_eq!(image.sample(3, 0), Some(Pixel::RgbF32([1.0, 0.0, 0.0])));
+    }
+
+    #[test]
+    fn difference_region_test() {
+        let image = Image::with_color32(8, 8, Color32::RED, Format::RgbU8).unwrap();
+        let mut changed = image.clone();
+
+        //bottom right corner turns blue
+        if let Data::U8(data) = &mut changed.data {
+            for (x, y) in [(6, 6), (7, 6), (6, 7), (7, 7)] {
+                let index = (y * 8 + x) * 3;
+                data[index..index + 3].copy_from_slice(&[0, 0, 255]);
+            }
+        }
+
+        assert_eq!(image.difference_region(&image.clone(), 0.0).unwrap(), None);
+        assert_eq!(
+            image.difference_region(&changed, 0.01).unwrap(),
+            Some(URect::new(6, 6, 2, 2))
+        );
+
+        let other_size = Image::with_color32(4, 4, Color32::RED, Format::RgbU8).unwrap();
+        assert!(image.difference_region(&other_size, 0.0).is_err());
+    }
 }
@@ -1,12 +1,19 @@
 #![warn(clippy::nursery)]
 #![warn(clippy::perf)]
 #![warn(clippy::pedantic)]
+#![cfg_attr(all(not(feature = "std"), not(test)), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("either the `std` or the `libm` feature is required for the math functions");
+
+extern crate alloc;
 
 pub mod color32;
 pub mod gen_vec;
+#[cfg(feature = "std")]
 pub mod image;
 
-pub use color32::Color32;
+pub use color32::{Color32, ColorBlindness};
 
 pub mod math {
 
@@ -27,7 +34,7 @@ pub mod math {
     pub type IRect = rect::Rect<i32>;
 
     mod rect {
-        use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+        use core::ops::{Add, AddAssign, Mul, Sub, SubAssign};
 
         #[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
         pub struct Rect<T> {
@@ -61,3 +68,38 @@ pub mod math {
         }
     }
 }
+
+/// Float functions that aren't part of `core`, provided by either `std` or `libm`
+//the std functions could be const, but the libm ones can't
+#[allow(clippy::missing_const_for_fn)]
+mod float {
+    #[cfg(feature = "std")]
+    pub fn powf(x: f32, y: f32) -> f32 {
+        x.powf(y)
+    }
+
+    #[cfg(not(feature = "std"))]
+    pub fn powf(x: f32, y: f32) -> f32 {
+        libm::powf(x, y)
+    }
+
+    #[cfg(feature = "std")]
+    pub fn round(x: f32) -> f32 {
+        x.round()
+    }
+
+    #[cfg(not(feature = "std"))]
+    pub fn round(x: f32) -> f32 {
+        libm::roundf(x)
+    }
+
+    #[cfg(feature = "std")]
+    pub fn mul_add(x: f32, a: f32, b: f32) -> f32 {
+        x.mul_add(a, b)
+    }
+
+    #[cfg(not(feature = "std"))]
+    pub fn mul_add(x: f32, a: f32, b: f32) -> f32 {
+        libm::fmaf(x, a, b)
+    }
+}
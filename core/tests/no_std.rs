@@ -0,0 +1,28 @@
+//! Uses the parts of the crate that are available without the standard library.
+//!
+//! How to run:
+//! cargo test -p cac-core --no-default-features --features libm --test no_std
+#![no_std]
+
+use cac_core::{gen_vec::GenVec, math::vec2, Color32};
+
+#[test]
+fn color_lerp() {
+    let grey = Color32::BLACK.lerp(Color32::WHITE, 0.5);
+
+    assert_eq!(grey, Color32::from_rgb(0.5, 0.5, 0.5));
+    assert_eq!(Color32::RED.lerp(Color32::BLUE, 1.0), Color32::BLUE);
+    assert_eq!(Color32::RED.to_u32(), 0xFF00_00FF);
+}
+
+#[test]
+fn gen_vec_insert() {
+    struct Key;
+    let mut storage = GenVec::<Key, _>::new();
+
+    let handle = storage.insert(vec2(1.0, 2.0));
+    assert_eq!(storage.get(handle), Some(&vec2(1.0, 2.0)));
+
+    assert_eq!(storage.remove(handle), Some(vec2(1.0, 2.0)));
+    assert_eq!(storage.get(handle), None);
+}